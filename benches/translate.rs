@@ -0,0 +1,46 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use i18n_string::{
+    I18nString, TranslateOptions,
+    cache::TemplateCache,
+    catalog::{Catalog, Message},
+};
+
+const ITERATIONS: u32 = 200_000;
+
+fn bench<F: FnMut() -> String>(name: &str, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let elapsed = start.elapsed();
+    println!("{name:<10} {:>8.1} ns/iter", elapsed.as_nanos() as f64 / ITERATIONS as f64);
+    elapsed
+}
+
+fn main() {
+    let mut catalog = Catalog::new();
+    catalog.insert(Message::new(
+        "{0} uploaded {1} files to {2} in {3} seconds, {4} of them were skipped",
+        "{0} hat {1} Dateien in {3} Sekunden nach {2} hochgeladen, {4} davon wurden übersprungen",
+    ));
+    let s = I18nString::template(
+        "{0} uploaded {1} files to {2} in {3} seconds, {4} of them were skipped",
+        [
+            I18nString::literal("Alice"),
+            I18nString::literal("12"),
+            I18nString::literal("/home/alice/documents"),
+            I18nString::literal("3"),
+            I18nString::literal("2"),
+        ],
+    );
+    let options = TranslateOptions::new();
+    let cache = TemplateCache::new(&catalog, 64);
+
+    let uncached = bench("uncached", || s.translate_with(&catalog, &options));
+    let cached = bench("cached", || cache.translate(&s, &options));
+    println!("speedup    {:>8.2}x", uncached.as_secs_f64() / cached.as_secs_f64());
+}
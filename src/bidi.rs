@@ -0,0 +1,103 @@
+/// Left-to-right isolate.
+pub const LRI: char = '\u{2066}';
+/// Right-to-left isolate.
+pub const RLI: char = '\u{2067}';
+/// First strong isolate.
+pub const FSI: char = '\u{2068}';
+/// Pop directional isolate.
+pub const PDI: char = '\u{2069}';
+
+/// Text direction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Direction {
+    /// Left-to-right.
+    Ltr,
+    /// Right-to-left.
+    Rtl,
+}
+
+impl Direction {
+    /// Get the direction of a language from its language subtag (or a full language tag).
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::bidi::Direction;
+    ///
+    /// assert_eq!(Direction::from_language("ar-EG"), Direction::Rtl);
+    /// assert_eq!(Direction::from_language("en"), Direction::Ltr);
+    /// ```
+    pub fn from_language(tag: &str) -> Self {
+        const RTL_LANGUAGES: [&str; 14] = [
+            "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ps", "sd", "syr", "ug", "ur", "yi",
+        ];
+
+        let language = tag.split(['-', '_']).next().unwrap_or(tag);
+        if RTL_LANGUAGES.iter().any(|l| l.eq_ignore_ascii_case(language)) {
+            Direction::Rtl
+        } else {
+            Direction::Ltr
+        }
+    }
+
+    /// Get the isolate initiator for text of this direction.
+    pub fn isolate(self) -> char {
+        match self {
+            Direction::Ltr => LRI,
+            Direction::Rtl => RLI,
+        }
+    }
+}
+
+fn is_rtl(c: char) -> bool {
+    matches!(c,
+        '\u{05BE}' | '\u{05C0}' | '\u{05C3}' | '\u{05C6}' | '\u{05D0}'..='\u{05FF}'
+        | '\u{0608}' | '\u{060B}' | '\u{060D}' | '\u{061B}'..='\u{064A}' | '\u{066D}'..='\u{066F}'
+        | '\u{0671}'..='\u{06D5}' | '\u{06E5}'..='\u{06E6}' | '\u{06EE}'..='\u{06EF}' | '\u{06FA}'..='\u{08FF}'
+        | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}'
+        | '\u{10800}'..='\u{10FFF}' | '\u{1E800}'..='\u{1EFFF}'
+    )
+}
+
+fn strong_direction(c: char) -> Option<Direction> {
+    if is_rtl(c) {
+        Some(Direction::Rtl)
+    } else if c.is_alphabetic() && !('\u{0590}'..='\u{08FF}').contains(&c) {
+        Some(Direction::Ltr)
+    } else {
+        None
+    }
+}
+
+/// Detect the direction of a string from its first strong character.
+///
+/// Returns `None` if the string contains no strongly directional characters (e.g. digits or punctuation only).
+/// Characters inside existing isolates are skipped, as the Unicode bidi algorithm does.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::bidi::{Direction, direction};
+///
+/// assert_eq!(direction("hello"), Some(Direction::Ltr));
+/// assert_eq!(direction("123 שלום"), Some(Direction::Rtl));
+/// assert_eq!(direction("123"), None);
+/// ```
+pub fn direction(s: &str) -> Option<Direction> {
+    let mut depth = 0usize;
+    for c in s.chars() {
+        match c {
+            LRI | RLI | FSI => depth += 1,
+            PDI => depth = depth.saturating_sub(1),
+            _ if depth == 0 => {
+                if let Some(d) = strong_direction(c) {
+                    return Some(d);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,61 @@
+use std::{
+    io::{self, Read, Write},
+    path::Path,
+    process::ExitCode,
+};
+
+use i18n_string::catalog::{Catalog, CatalogFormat};
+
+fn format(explicit: Option<&str>, path: &str) -> io::Result<CatalogFormat> {
+    let format = match explicit {
+        Some(name) => name.parse().ok(),
+        None => Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(CatalogFormat::from_extension),
+    };
+    format.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("unknown catalog format of {path}")))
+}
+
+/// Convert a catalog between formats, `-` reads stdin or writes stdout.
+pub fn run(args: Vec<String>) -> io::Result<ExitCode> {
+    let mut from = None;
+    let mut to = None;
+    let mut paths = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => from = args.next(),
+            "--to" => to = args.next(),
+            _ => paths.push(arg),
+        }
+    }
+    let [input, output] = paths.as_slice() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "convert needs an input and an output",
+        ));
+    };
+
+    let content = if input == "-" {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        std::fs::read_to_string(input)?
+    };
+    let catalog = Catalog::parse_as(&content, format(from.as_deref(), input)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{input}: {e}")))?;
+
+    let mut converted = String::new();
+    catalog
+        .write_as(&mut converted, format(to.as_deref(), output)?)
+        .map_err(io::Error::other)?;
+    if output == "-" {
+        io::stdout().write_all(converted.as_bytes())?;
+    } else {
+        std::fs::write(output, converted)?;
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
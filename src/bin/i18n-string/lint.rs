@@ -0,0 +1,47 @@
+use std::{io, process::ExitCode};
+
+use i18n_string::{
+    catalog::Catalog,
+    lint::{Issue, Severity, lint, write_json_reports},
+};
+
+/// Lint each translated catalog of any format, exits with 1 if any error was found.
+pub fn run(args: Vec<String>) -> io::Result<ExitCode> {
+    let mut json = false;
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ => paths.push(arg),
+        }
+    }
+    if paths.len() < 2 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "lint needs a source catalog and at least one translated catalog",
+        ));
+    }
+
+    let source = Catalog::load(&paths[0])?;
+    let mut failed = false;
+    let mut reports: Vec<(&str, Vec<Issue>)> = Vec::new();
+    for path in &paths[1..] {
+        let issues = lint(&source, &Catalog::load(path)?);
+        failed |= issues.iter().any(|issue| issue.kind.severity() == Severity::Error);
+        reports.push((path, issues));
+    }
+
+    if json {
+        let mut output = String::new();
+        write_json_reports(&mut output, reports.iter().map(|(path, issues)| (*path, &issues[..]))).map_err(io::Error::other)?;
+        println!("{output}");
+    } else {
+        for (path, issues) in &reports {
+            for issue in issues {
+                println!("{path}: {issue}");
+            }
+        }
+    }
+
+    Ok(if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
@@ -0,0 +1,46 @@
+use std::process::ExitCode;
+
+mod convert;
+mod lint;
+mod message;
+
+const USAGE: &str = "\
+usage: i18n-string <command> [args...]
+
+commands:
+    lint [--json] <source> <translated>...           check translated catalogs against a source catalog
+    convert [--from <format>] [--to <format>] <input> <output>
+                                                     convert a catalog, formats: po, json, toml, xliff, android, strings
+    parse [--pretty] [<file>...]                     validate serialized strings and print them normalized
+    tree [<file>...]                                 print serialized strings as indented trees
+    translate [--catalog <path>] [--locale <tag>] [<file>...]
+                                                     translate serialized strings
+
+Serialized strings are separated by whitespace and read from stdin if no file is given.";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("lint") => lint::run(args.collect()),
+        Some("convert") => convert::run(args.collect()),
+        Some("parse") => message::run_parse(args.collect()),
+        Some("tree") => message::run_tree(args.collect()),
+        Some("translate") => message::run_translate(args.collect()),
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("i18n-string: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
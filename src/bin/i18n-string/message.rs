@@ -0,0 +1,128 @@
+use std::{
+    fs,
+    io::{self, Read},
+    process::ExitCode,
+};
+
+use i18n_string::{I18nString, NoResolver, TranslateOptions, catalog::Catalog, locale::Locale, stream::Messages};
+
+/// Call `f` with each serialized string of the files, or stdin if there are none.
+///
+/// Strings may span multiple lines, e.g. the output of `parse --pretty`. Invalid strings are reported with
+/// the line they start on and skipped to the end of the line, returns whether all strings were valid.
+fn for_each(paths: &[String], mut f: impl FnMut(I18nString)) -> io::Result<bool> {
+    let stdin = [String::from("-")];
+    let paths = if paths.is_empty() { &stdin[..] } else { paths };
+
+    let mut valid = true;
+    for path in paths {
+        let input = match path.as_str() {
+            "-" => {
+                let mut input = String::new();
+                io::stdin().lock().read_to_string(&mut input)?;
+                input
+            }
+            path => fs::read_to_string(path)?,
+        };
+
+        let (mut line, mut position) = (1, 0);
+        for (span, result) in Messages::new(&input) {
+            line += input[position..span.start].matches('\n').count();
+            position = span.start;
+            match result {
+                Ok(s) => f(s),
+                Err(e) => {
+                    eprintln!("{path}:{line}: {e}");
+                    valid = false;
+                }
+            }
+        }
+    }
+    Ok(valid)
+}
+
+fn exit_code(valid: bool) -> ExitCode {
+    if valid { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+/// Validate serialized strings and print them normalized, over multiple lines with `--pretty`.
+pub fn run_parse(args: Vec<String>) -> io::Result<ExitCode> {
+    let pretty = args.iter().any(|arg| arg == "--pretty");
+    let paths: Vec<String> = args.into_iter().filter(|arg| arg != "--pretty").collect();
+    for_each(&paths, |s| if pretty { println!("{s:#}") } else { println!("{s}") }).map(exit_code)
+}
+
+/// Write a string and its arguments as a tree, with an explicit stack instead of recursion.
+fn write_tree(output: &mut String, s: &I18nString) {
+    // nodes with the start of their line and the indentation of their children
+    let mut stack = vec![(String::new(), String::new(), s)];
+    while let Some((line, indent, s)) = stack.pop() {
+        output.push_str(&line);
+        let children: Vec<(Option<usize>, &I18nString)> = match s {
+            I18nString::Template(key, args) => {
+                output.push_str(&I18nString::template(key.clone(), []).to_string());
+                args.iter().enumerate().map(|(index, arg)| (Some(index), arg)).collect()
+            }
+            I18nString::Sensitive(inner) => {
+                output.push_str("s!");
+                vec![(None, &**inner)]
+            }
+            s => {
+                output.push_str(&s.to_string());
+                Vec::new()
+            }
+        };
+        output.push('\n');
+
+        // push in reverse, so the first child is written next
+        let count = children.len();
+        for (n, (index, child)) in children.into_iter().enumerate().rev() {
+            let last = n + 1 == count;
+            let mut line = format!("{indent}{}", if last { "└── " } else { "├── " });
+            if let Some(index) = index {
+                line.push_str(&format!("{{{index}}} "));
+            }
+            stack.push((line, format!("{indent}{}", if last { "    " } else { "│   " }), child));
+        }
+    }
+}
+
+/// Print serialized strings as indented trees.
+pub fn run_tree(paths: Vec<String>) -> io::Result<ExitCode> {
+    for_each(&paths, |s| {
+        let mut output = String::new();
+        write_tree(&mut output, &s);
+        print!("{output}");
+    })
+    .map(exit_code)
+}
+
+/// Translate serialized strings with an optional catalog and locale.
+pub fn run_translate(args: Vec<String>) -> io::Result<ExitCode> {
+    let mut catalog = None;
+    let mut options = TranslateOptions::new();
+    let mut paths = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--catalog" => {
+                let path = args.next().ok_or(io::ErrorKind::InvalidInput)?;
+                catalog = Some(Catalog::load(&path).map_err(|e| io::Error::new(e.kind(), format!("{path}: {e}")))?);
+            }
+            "--locale" => {
+                let tag = args.next().ok_or(io::ErrorKind::InvalidInput)?;
+                let locale: Locale = tag
+                    .parse()
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid locale {tag}")))?;
+                options = options.locale(locale);
+            }
+            _ => paths.push(arg),
+        }
+    }
+
+    for_each(&paths, |s| match &catalog {
+        Some(catalog) => println!("{}", s.translate_with(catalog, &options)),
+        None => println!("{}", s.translate_with(NoResolver, &options)),
+    })
+    .map(exit_code)
+}
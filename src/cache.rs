@@ -0,0 +1,284 @@
+use std::{
+    borrow::{Borrow, Cow},
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    I18nString, Resolver, TemplateKey, TranslateOptions,
+    locale::Locale,
+    template::{CompiledTemplate, TemplateStore},
+    translate::translate_to,
+};
+
+/// Key of a cached template, the locale of the options and the template key before resolving.
+#[derive(Clone, PartialEq, Eq)]
+struct Key {
+    locale: Option<Locale>,
+    template: TemplateKey,
+}
+
+/// Borrowed view of a key, so the map can be searched without allocating an owned key.
+trait KeyRef {
+    fn key(&self) -> (Option<&Locale>, &TemplateKey);
+}
+
+impl KeyRef for Key {
+    fn key(&self) -> (Option<&Locale>, &TemplateKey) {
+        (self.locale.as_ref(), &self.template)
+    }
+}
+
+impl KeyRef for (Option<&Locale>, &TemplateKey) {
+    fn key(&self) -> (Option<&Locale>, &TemplateKey) {
+        *self
+    }
+}
+
+impl<'a> Borrow<dyn KeyRef + 'a> for Key {
+    fn borrow(&self) -> &(dyn KeyRef + 'a) {
+        self
+    }
+}
+
+impl Hash for dyn KeyRef + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl PartialEq for dyn KeyRef + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for dyn KeyRef + '_ {}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+const NIL: usize = usize::MAX;
+
+struct Entry {
+    key: Key,
+    template: Arc<CompiledTemplate>,
+    prev: usize,
+    next: usize,
+}
+
+/// Least recently used map, entries are linked from the most recently used `head` to the `tail`.
+struct Lru {
+    map: HashMap<Key, usize>,
+    entries: Vec<Entry>,
+    head: usize,
+    tail: usize,
+}
+
+impl Lru {
+    fn unlink(&mut self, idx: usize) {
+        let (prev, next) = (self.entries[idx].prev, self.entries[idx].next);
+        match prev {
+            NIL => self.head = next,
+            prev => self.entries[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.entries[next].prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, idx: usize) {
+        self.entries[idx].prev = NIL;
+        self.entries[idx].next = self.head;
+        match self.head {
+            NIL => self.tail = idx,
+            head => self.entries[head].prev = idx,
+        }
+        self.head = idx;
+    }
+
+    fn get(&mut self, key: (Option<&Locale>, &TemplateKey)) -> Option<Arc<CompiledTemplate>> {
+        let idx = *self.map.get(&key as &dyn KeyRef)?;
+        if idx != self.head {
+            self.unlink(idx);
+            self.push_front(idx);
+        }
+        Some(self.entries[idx].template.clone())
+    }
+
+    fn insert(&mut self, key: Key, template: Arc<CompiledTemplate>, capacity: usize) {
+        if let Some(&idx) = self.map.get(&key) {
+            self.entries[idx].template = template;
+            self.unlink(idx);
+            self.push_front(idx);
+            return;
+        }
+
+        let idx = if self.entries.len() < capacity {
+            self.entries.push(Entry {
+                key: key.clone(),
+                template,
+                prev: NIL,
+                next: NIL,
+            });
+            self.entries.len() - 1
+        } else {
+            let idx = self.tail;
+            self.unlink(idx);
+            self.map.remove(&self.entries[idx].key);
+            self.entries[idx].key = key.clone();
+            self.entries[idx].template = template;
+            idx
+        };
+        self.map.insert(key, idx);
+        self.push_front(idx);
+    }
+}
+
+/// A resolver with a bounded cache of compiled templates, keyed by template key and locale.
+///
+/// Translating through the cache resolves and scans each template once, later translations of the same
+/// template reuse its text runs and argument slots. The least recently used templates are evicted when
+/// the cache is full.
+///
+/// The cache owns its resolver, so every cached template was resolved by it. Cached templates are not
+/// invalidated when the translations of the resolver change, [`clear`](Self::clear) the cache after reloading
+/// catalogs. As a [`Resolver`] the cache resolves through the wrapped resolver without caching.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{I18nString, TranslateOptions, cache::TemplateCache, catalog::{Catalog, Message}};
+///
+/// let mut catalog = Catalog::new();
+/// catalog.insert(Message::new("Hello {0}", "Hallo {0}"));
+///
+/// let cache = TemplateCache::new(catalog, 256);
+/// let options = TranslateOptions::new();
+/// for name in ["Alice", "Bob"] {
+///     let s = I18nString::template("Hello {0}", [I18nString::literal(name)]);
+///     assert_eq!(cache.translate(&s, &options), format!("Hallo {name}"));
+/// }
+/// assert_eq!(cache.len(), 1);
+/// ```
+pub struct TemplateCache<R> {
+    inner: R,
+    capacity: usize,
+    lru: Mutex<Lru>,
+}
+
+impl<R> TemplateCache<R> {
+    /// Create a new `TemplateCache` wrapping a resolver and holding at most `capacity` templates, a zero
+    /// capacity disables caching.
+    pub fn new(inner: R, capacity: usize) -> Self {
+        Self {
+            inner,
+            capacity,
+            lru: Mutex::new(Lru {
+                map: HashMap::new(),
+                entries: Vec::new(),
+                head: NIL,
+                tail: NIL,
+            }),
+        }
+    }
+
+    /// Get the wrapped resolver.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get the wrapped resolver, discarding the cached templates.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Get the maximum number of cached templates.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of cached templates.
+    pub fn len(&self) -> usize {
+        self.lru.lock().unwrap_or_else(|e| e.into_inner()).entries.len()
+    }
+
+    /// Check whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all cached templates.
+    pub fn clear(&self) {
+        let mut lru = self.lru.lock().unwrap_or_else(|e| e.into_inner());
+        lru.map.clear();
+        lru.entries.clear();
+        lru.head = NIL;
+        lru.tail = NIL;
+    }
+}
+
+impl<R: Resolver> TemplateCache<R> {
+    /// Translate an `I18nString` with the wrapped resolver and the given options, compiling templates through
+    /// the cache.
+    ///
+    /// Like `I18nString::translate_with`, the translation stops at an exceeded limit of the options.
+    pub fn translate(&self, s: &I18nString, options: &TranslateOptions) -> String {
+        let mut res = String::with_capacity(32);
+        let _ = translate_to(s, &mut res, &self.inner, options, Some(self));
+        res
+    }
+}
+
+impl<R: Resolver> Resolver for TemplateCache<R> {
+    fn resolve<'s>(&'s self, template: &'s str) -> Cow<'s, str> {
+        self.inner.resolve(template)
+    }
+
+    fn lookup<'s>(&'s self, template: &'s str) -> Option<Cow<'s, str>> {
+        self.inner.lookup(template)
+    }
+
+    fn resolve_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Cow<'s, str> {
+        self.inner.resolve_in_context(context, template)
+    }
+
+    fn lookup_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Option<Cow<'s, str>> {
+        self.inner.lookup_in_context(context, template)
+    }
+}
+
+impl<R> TemplateStore for TemplateCache<R> {
+    fn get_or_compile(
+        &self,
+        locale: Option<&Locale>,
+        template: &TemplateKey,
+        resolve: &mut dyn FnMut() -> CompiledTemplate,
+    ) -> Arc<CompiledTemplate> {
+        if self.capacity == 0 {
+            return Arc::new(resolve());
+        }
+
+        if let Some(compiled) = self.lru.lock().unwrap_or_else(|e| e.into_inner()).get((locale, template)) {
+            return compiled;
+        }
+
+        // compile without holding the lock, resolvers may be slow or translate themselves
+        let compiled = Arc::new(resolve());
+        let key = Key {
+            locale: locale.cloned(),
+            template: template.clone(),
+        };
+        self.lru
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, compiled.clone(), self.capacity);
+        compiled
+    }
+}
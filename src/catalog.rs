@@ -0,0 +1,648 @@
+use alloc::{borrow::Cow, collections::BTreeMap, string::String, vec::Vec};
+
+use compact_str::CompactString;
+
+use crate::{
+    I18nString, InvalidFormat, Resolver, TemplateKey,
+    visit::{Visit, walk_template},
+};
+
+mod android;
+mod apple;
+mod json;
+mod printf;
+mod toml;
+mod xliff;
+mod xml;
+
+/// A file format of catalogs.
+///
+/// Placeholders are converted to the native syntax of each format: `%1$s` for Android, `%1$@` for Apple and
+/// `<x/>` elements for XLIFF, the other formats keep `{0}` as is.
+/// Android and Apple resources are keyed by the message id and have no header, source plural or fuzzy flag,
+/// these are lost when converting to them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CatalogFormat {
+    /// gettext `.po` and `.pot` files.
+    Po,
+    /// JSON with a `header` string and a `messages` array of message objects.
+    Json,
+    /// TOML with a `header` string and `[[messages]]` tables.
+    Toml,
+    /// XLIFF 1.2, plural forms are grouped like gettext does.
+    Xliff,
+    /// Android `strings.xml` resources.
+    AndroidXml,
+    /// Apple `.strings` files, plural forms are keyed as `key[N]`.
+    AppleStrings,
+}
+
+impl CatalogFormat {
+    /// Guess the format from a file extension, e.g. `po` or `xlf`.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::catalog::CatalogFormat;
+    ///
+    /// assert_eq!(CatalogFormat::from_extension("pot"), Some(CatalogFormat::Po));
+    /// assert_eq!(CatalogFormat::from_extension("XLIFF"), Some(CatalogFormat::Xliff));
+    /// assert_eq!(CatalogFormat::from_extension("txt"), None);
+    /// ```
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "po" | "pot" => Some(CatalogFormat::Po),
+            "json" => Some(CatalogFormat::Json),
+            "toml" => Some(CatalogFormat::Toml),
+            "xlf" | "xliff" => Some(CatalogFormat::Xliff),
+            "xml" => Some(CatalogFormat::AndroidXml),
+            "strings" => Some(CatalogFormat::AppleStrings),
+            _ => None,
+        }
+    }
+
+    /// Get the name of the format, also accepted by `FromStr`.
+    pub fn name(self) -> &'static str {
+        match self {
+            CatalogFormat::Po => "po",
+            CatalogFormat::Json => "json",
+            CatalogFormat::Toml => "toml",
+            CatalogFormat::Xliff => "xliff",
+            CatalogFormat::AndroidXml => "android",
+            CatalogFormat::AppleStrings => "strings",
+        }
+    }
+}
+
+impl core::str::FromStr for CatalogFormat {
+    type Err = InvalidFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "android" => Ok(CatalogFormat::AndroidXml),
+            _ => Self::from_extension(s).ok_or(InvalidFormat),
+        }
+    }
+}
+
+/// Maximum number of plural forms of a message, one per CLDR plural category.
+const MAX_PLURAL_FORMS: usize = 6;
+
+/// Prefix of the extracted comments holding the source text of a message keyed by id, one per line.
+const SOURCE_TEXT_PREFIX: &str = "Source text: ";
+
+/// A message of a catalog, with its translations.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Message {
+    /// Disambiguating context (`msgctxt`).
+    pub context: Option<CompactString>,
+    /// Source template (`msgid`).
+    pub id: CompactString,
+    /// Plural source template (`msgid_plural`).
+    pub id_plural: Option<CompactString>,
+    /// Translations (`msgstr`, or `msgstr[N]` for plural messages).
+    pub translations: Vec<CompactString>,
+    /// Comments for translators extracted from the source (`#.`), one per line.
+    pub comments: Vec<CompactString>,
+    /// Whether the translation needs review (`#, fuzzy`), fuzzy translations are not used.
+    pub fuzzy: bool,
+}
+
+impl Message {
+    /// Create a new singular message.
+    pub fn new<I: Into<CompactString>, T: Into<CompactString>>(id: I, translation: T) -> Self {
+        Self {
+            id: id.into(),
+            translations: [translation.into()].into(),
+            ..Self::default()
+        }
+    }
+
+    /// Get the source text extracted for a message keyed by id, `None` if it has none.
+    ///
+    /// [`Catalog::extract`] keeps the text of templates with an id in `Source text:` comments.
+    pub fn source_text(&self) -> Option<String> {
+        let mut lines = self
+            .comments
+            .iter()
+            .filter_map(|c| c.strip_prefix(SOURCE_TEXT_PREFIX))
+            .peekable();
+        lines.peek()?;
+        Some(lines.collect::<Vec<_>>().join("\n"))
+    }
+
+    /// Get the translation used for resolving, `None` if it is empty or fuzzy.
+    pub fn translation(&self) -> Option<&str> {
+        match self.translations.first() {
+            Some(t) if !t.is_empty() && !self.fuzzy => Some(t),
+            _ => None,
+        }
+    }
+}
+
+/// Key of a message, the context and the id joined with `\x04` like gettext does.
+fn key(context: Option<&str>, id: &str) -> CompactString {
+    match context {
+        Some(context) => compact_str::format_compact!("{context}\x04{id}"),
+        None => id.into(),
+    }
+}
+
+/// A translation catalog, messages keyed by their context and source template.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{I18nString, catalog::Catalog};
+///
+/// let catalog = Catalog::parse_po(r#"
+/// msgid "hello {0}"
+/// msgstr "bonjour {0}"
+/// "#).unwrap();
+///
+/// let s = I18nString::template("hello {0}", [I18nString::literal("Alice")]);
+/// assert_eq!(s.translate(&catalog), "bonjour Alice");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Catalog {
+    header: CompactString,
+    messages: BTreeMap<CompactString, Message>,
+}
+
+impl Catalog {
+    /// Create a new empty `Catalog`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the header (the translation of the empty `msgid`).
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+
+    /// Get a header field, e.g. `Plural-Forms`.
+    pub fn header_field(&self, name: &str) -> Option<&str> {
+        self.header.lines().find_map(|line| {
+            let (field, value) = line.split_once(':')?;
+            field.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+
+    /// Get the number of plural forms declared by the `Plural-Forms` header, e.g. `nplurals=3; plural=...`.
+    pub fn nplurals(&self) -> Option<usize> {
+        self.header_field("Plural-Forms")?.split(';').find_map(|part| {
+            part.trim()
+                .strip_prefix("nplurals")?
+                .trim()
+                .strip_prefix('=')?
+                .trim()
+                .parse()
+                .ok()
+        })
+    }
+
+    /// Set the header.
+    pub fn set_header<S: Into<CompactString>>(&mut self, header: S) {
+        self.header = header.into();
+    }
+
+    /// Insert a message, replacing the message with the same context and id.
+    pub fn insert(&mut self, message: Message) -> Option<Message> {
+        self.messages.insert(key(message.context.as_deref(), &message.id), message)
+    }
+
+    /// Get a message by its context and id.
+    pub fn get(&self, context: Option<&str>, id: &str) -> Option<&Message> {
+        self.messages.get(&key(context, id))
+    }
+
+    /// Iterate all messages.
+    pub fn messages(&self) -> impl Iterator<Item = &Message> {
+        self.messages.values()
+    }
+
+    /// Get the number of messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Check whether the catalog has no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Parse a catalog from gettext `.po` content.
+    pub fn parse_po(input: &str) -> Result<Self, InvalidFormat> {
+        po::parse(input)
+    }
+
+    /// Load a catalog from a gettext `.po` file.
+    #[cfg(feature = "std")]
+    pub fn load_po<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse_po(&content).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Write the catalog as gettext `.po` content.
+    pub fn write_po<W: core::fmt::Write>(&self, output: &mut W) -> core::fmt::Result {
+        po::write(self, output)
+    }
+
+    /// Parse a catalog from content of a format.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::catalog::{Catalog, CatalogFormat};
+    ///
+    /// let catalog = Catalog::parse_as("\"greeting\" = \"Hallo %1$@!\";", CatalogFormat::AppleStrings).unwrap();
+    /// assert_eq!(catalog.to_po(), "msgid \"greeting\"\nmsgstr \"Hallo {0}!\"\n");
+    ///
+    /// let mut xml = String::new();
+    /// catalog.write_as(&mut xml, CatalogFormat::AndroidXml).unwrap();
+    /// assert!(xml.contains("<string name=\"greeting\">Hallo %1$s!</string>"));
+    /// ```
+    pub fn parse_as(input: &str, format: CatalogFormat) -> Result<Self, InvalidFormat> {
+        match format {
+            CatalogFormat::Po => po::parse(input),
+            CatalogFormat::Json => json::parse(input),
+            CatalogFormat::Toml => toml::parse(input),
+            CatalogFormat::Xliff => xliff::parse(input),
+            CatalogFormat::AndroidXml => android::parse(input),
+            CatalogFormat::AppleStrings => apple::parse(input),
+        }
+    }
+
+    /// Write the catalog as content of a format.
+    pub fn write_as<W: core::fmt::Write>(&self, output: &mut W, format: CatalogFormat) -> core::fmt::Result {
+        match format {
+            CatalogFormat::Po => po::write(self, output),
+            CatalogFormat::Json => json::write(self, output),
+            CatalogFormat::Toml => toml::write(self, output),
+            CatalogFormat::Xliff => xliff::write(self, output),
+            CatalogFormat::AndroidXml => android::write(self, output),
+            CatalogFormat::AppleStrings => apple::write(self, output),
+        }
+    }
+
+    /// Load a catalog from a file, the format is guessed from the extension and defaults to `.po`.
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let format = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(CatalogFormat::from_extension)
+            .unwrap_or(CatalogFormat::Po);
+        let content = std::fs::read_to_string(path)?;
+        Self::parse_as(&content, format).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Add untranslated messages for all templates of a string, with the notes of the templates as comments.
+    ///
+    /// Templates are keyed by their id if they have one, otherwise by their source text. The source text of
+    /// templates with an id is kept in `Source text:` comments, see [`Message::source_text`].
+    /// Existing messages keep their translations and get missing comments added.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{I18nString, TemplateKey, catalog::Catalog};
+    ///
+    /// let key = TemplateKey::new("{0} deleted").with_note("Shown after deleting a file").with_placeholder_note(0, "file name");
+    /// let mut catalog = Catalog::new();
+    /// catalog.extract(&I18nString::template(key, [I18nString::literal("a.txt")]));
+    /// assert_eq!(
+    ///     catalog.to_po(),
+    ///     "#. Shown after deleting a file\n#. {0}: file name\nmsgid \"{0} deleted\"\nmsgstr \"\"\n"
+    /// );
+    ///
+    /// let mut catalog = Catalog::new();
+    /// catalog.extract(&I18nString::template(TemplateKey::new("{0} not found").with_id("err.nf"), []));
+    /// assert_eq!(catalog.to_po(), "#. Source text: {0} not found\nmsgid \"err.nf\"\nmsgstr \"\"\n");
+    /// assert_eq!(catalog.get(None, "err.nf").unwrap().source_text().as_deref(), Some("{0} not found"));
+    /// ```
+    pub fn extract(&mut self, s: &I18nString) {
+        struct Extractor<'c>(&'c mut Catalog);
+
+        impl Visit for Extractor<'_> {
+            fn visit_template(&mut self, template: &TemplateKey, args: &[I18nString]) {
+                let mut comments: Vec<CompactString> = match template.id() {
+                    Some(_) => template
+                        .text()
+                        .split('\n')
+                        .map(|line| compact_str::format_compact!("{SOURCE_TEXT_PREFIX}{line}"))
+                        .collect(),
+                    None => Vec::new(),
+                };
+                comments.extend(template.note().into_iter().flat_map(str::lines).map(Into::into));
+                for (index, note) in template.placeholder_notes() {
+                    comments.extend(note.lines().map(|line| compact_str::format_compact!("{{{index}}}: {line}")));
+                }
+
+                let key = key(template.context(), template.lookup_key());
+                match self.0.messages.get_mut(&key) {
+                    Some(message) => {
+                        for comment in comments {
+                            if !message.comments.contains(&comment) {
+                                message.comments.push(comment);
+                            }
+                        }
+                    }
+                    None => {
+                        self.0.messages.insert(
+                            key,
+                            Message {
+                                context: template.context().map(Into::into),
+                                comments,
+                                ..Message::new(template.lookup_key(), "")
+                            },
+                        );
+                    }
+                }
+
+                walk_template(self, template, args);
+            }
+        }
+
+        Extractor(self).visit_string(s);
+    }
+
+    /// Format the catalog as gettext `.po` content.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::catalog::{Catalog, Message};
+    ///
+    /// let mut catalog = Catalog::new();
+    /// catalog.insert(Message::new("Say \"hi\"", ""));
+    /// assert_eq!(catalog.to_po(), "msgid \"Say \\\"hi\\\"\"\nmsgstr \"\"\n");
+    /// ```
+    pub fn to_po(&self) -> String {
+        let mut output = String::new();
+        let _ = self.write_po(&mut output);
+        output
+    }
+}
+
+impl Extend<Message> for Catalog {
+    fn extend<T: IntoIterator<Item = Message>>(&mut self, iter: T) {
+        for message in iter {
+            self.insert(message);
+        }
+    }
+}
+
+impl Resolver for Catalog {
+    fn resolve<'s>(&'s self, template: &'s str) -> Cow<'s, str> {
+        self.lookup(template).unwrap_or(Cow::Borrowed(template))
+    }
+
+    fn lookup<'s>(&'s self, template: &'s str) -> Option<Cow<'s, str>> {
+        self.messages.get(template).and_then(Message::translation).map(Cow::Borrowed)
+    }
+
+    fn resolve_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Cow<'s, str> {
+        self.lookup_in_context(context, template).unwrap_or(Cow::Borrowed(template))
+    }
+
+    fn lookup_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Option<Cow<'s, str>> {
+        match context {
+            Some(context) => self
+                .get(Some(context), template)
+                .and_then(Message::translation)
+                .map(Cow::Borrowed),
+            None => self.lookup(template),
+        }
+    }
+}
+
+mod po {
+    use core::fmt::Write;
+
+    use super::*;
+
+    fn write_string<W: Write>(output: &mut W, keyword: &str, value: &str) -> core::fmt::Result {
+        fn write_quoted<W: Write>(output: &mut W, value: &str) -> core::fmt::Result {
+            output.write_char('"')?;
+            for c in value.chars() {
+                match c {
+                    '"' => output.write_str("\\\"")?,
+                    '\\' => output.write_str("\\\\")?,
+                    '\n' => output.write_str("\\n")?,
+                    '\t' => output.write_str("\\t")?,
+                    '\r' => output.write_str("\\r")?,
+                    _ => output.write_char(c)?,
+                }
+            }
+            output.write_str("\"\n")
+        }
+
+        write!(output, "{keyword} ")?;
+        if value.trim_end_matches('\n').contains('\n') {
+            output.write_str("\"\"\n")?;
+            for line in value.split_inclusive('\n') {
+                write_quoted(output, line)?;
+            }
+            Ok(())
+        } else {
+            write_quoted(output, value)
+        }
+    }
+
+    pub fn write<W: Write>(catalog: &Catalog, output: &mut W) -> core::fmt::Result {
+        let mut first = true;
+        if !catalog.header.is_empty() {
+            write_string(output, "msgid", "")?;
+            write_string(output, "msgstr", &catalog.header)?;
+            first = false;
+        }
+
+        for message in catalog.messages() {
+            if !first {
+                output.write_char('\n')?;
+            }
+            first = false;
+
+            for comment in &message.comments {
+                for line in comment.lines() {
+                    match line {
+                        "" => output.write_str("#.\n")?,
+                        line => writeln!(output, "#. {line}")?,
+                    }
+                }
+            }
+            if message.fuzzy {
+                output.write_str("#, fuzzy\n")?;
+            }
+            if let Some(context) = &message.context {
+                write_string(output, "msgctxt", context)?;
+            }
+            write_string(output, "msgid", &message.id)?;
+            match &message.id_plural {
+                Some(id_plural) => {
+                    write_string(output, "msgid_plural", id_plural)?;
+                    for n in 0..message.translations.len().max(2) {
+                        let translation = message.translations.get(n).map_or("", |t| t.as_str());
+                        write_string(output, &alloc::format!("msgstr[{n}]"), translation)?;
+                    }
+                }
+                None => write_string(output, "msgstr", message.translations.first().map_or("", |t| t.as_str()))?,
+            }
+        }
+        Ok(())
+    }
+
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    enum Field {
+        Context,
+        Id,
+        IdPlural,
+        Translation(usize),
+    }
+
+    fn unquote(s: &str) -> Result<String, InvalidFormat> {
+        let s = s.trim();
+        let s = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).ok_or(InvalidFormat)?;
+
+        let mut output = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next().ok_or(InvalidFormat)? {
+                    'n' => output.push('\n'),
+                    't' => output.push('\t'),
+                    'r' => output.push('\r'),
+                    'a' => output.push('\x07'),
+                    'b' => output.push('\x08'),
+                    'f' => output.push('\x0c'),
+                    'v' => output.push('\x0b'),
+                    c => output.push(c),
+                }
+            } else if c == '"' {
+                return Err(InvalidFormat);
+            } else {
+                output.push(c);
+            }
+        }
+        Ok(output)
+    }
+
+    struct Builder {
+        catalog: Catalog,
+        message: Message,
+        field: Option<Field>,
+        value: String,
+    }
+
+    impl Builder {
+        fn flush_field(&mut self) {
+            let value = CompactString::from(core::mem::take(&mut self.value));
+            match self.field.take() {
+                Some(Field::Context) => self.message.context = Some(value),
+                Some(Field::Id) => self.message.id = value,
+                Some(Field::IdPlural) => self.message.id_plural = Some(value),
+                Some(Field::Translation(n)) => {
+                    if self.message.translations.len() <= n {
+                        self.message.translations.resize(n + 1, CompactString::default());
+                    }
+                    self.message.translations[n] = value;
+                }
+                None => {}
+            }
+        }
+
+        fn flush_message(&mut self) {
+            self.flush_field();
+            let message = core::mem::take(&mut self.message);
+            if message.id.is_empty() && message.context.is_none() {
+                if let Some(header) = message.translations.into_iter().next() {
+                    self.catalog.header = header;
+                }
+            } else if !message.translations.is_empty() || !message.id.is_empty() {
+                self.catalog.insert(message);
+            }
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Catalog, InvalidFormat> {
+        let mut builder = Builder {
+            catalog: Catalog::new(),
+            message: Message::default(),
+            field: None,
+            value: String::new(),
+        };
+        let mut has_message = false;
+
+        for line in input.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(comment) = line.strip_prefix('#') {
+                if builder.field.is_some_and(|f| matches!(f, Field::Translation(_))) {
+                    builder.flush_message();
+                    has_message = false;
+                }
+                if let Some(text) = comment.strip_prefix('.') {
+                    builder.message.comments.push(text.strip_prefix(' ').unwrap_or(text).into());
+                }
+                if let Some(flags) = comment.strip_prefix(',')
+                    && flags.split(',').any(|f| f.trim() == "fuzzy")
+                {
+                    builder.message.fuzzy = true;
+                }
+                continue;
+            }
+
+            if line.starts_with('"') {
+                if builder.field.is_none() {
+                    return Err(InvalidFormat);
+                }
+                builder.value.push_str(&unquote(line)?);
+                continue;
+            }
+
+            let (keyword, rest) = line.split_once(char::is_whitespace).ok_or(InvalidFormat)?;
+            let field = match keyword {
+                "msgctxt" => Field::Context,
+                "msgid" => Field::Id,
+                "msgid_plural" => Field::IdPlural,
+                "msgstr" => Field::Translation(0),
+                _ => {
+                    let n = keyword
+                        .strip_prefix("msgstr[")
+                        .and_then(|s| s.strip_suffix(']'))
+                        .and_then(|n| n.parse().ok())
+                        .filter(|&n| n < MAX_PLURAL_FORMS)
+                        .ok_or(InvalidFormat)?;
+                    Field::Translation(n)
+                }
+            };
+
+            let starts_message =
+                matches!(field, Field::Context) || (matches!(field, Field::Id) && builder.field != Some(Field::Context));
+            if starts_message && has_message {
+                builder.flush_message();
+            }
+            builder.flush_field();
+            has_message = true;
+            builder.field = Some(field);
+            builder.value = unquote(rest)?;
+        }
+
+        if has_message {
+            builder.flush_message();
+        }
+
+        Ok(builder.catalog)
+    }
+}
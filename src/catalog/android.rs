@@ -0,0 +1,222 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use compact_str::CompactString;
+
+use super::{
+    Catalog, Message, printf,
+    xml::{self, Event, Reader, attribute},
+};
+use crate::InvalidFormat;
+
+/// Namespace of the attribute keeping the context of a message, ignored by the Android build tools.
+const NAMESPACE: &str = "https://github.com/Kr328/i18n-string-rs";
+
+/// Plural categories in the order of gettext plural forms.
+const CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// Get the plural categories for a number of gettext plural forms.
+///
+/// Android selects items by CLDR category while gettext numbers the forms, the usual orders are assumed,
+/// e.g. `one, few, other` for 3 forms.
+fn quantities(plurals: usize) -> &'static [&'static str] {
+    match plurals {
+        0 | 1 => &CATEGORIES[5..],
+        2 => &["one", "other"],
+        3 => &["one", "few", "other"],
+        4 => &["one", "two", "few", "other"],
+        5 => &CATEGORIES[1..],
+        _ => &CATEGORIES,
+    }
+}
+
+fn escape_text(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '\\' => output.push_str("\\\\"),
+            '\'' => output.push_str("\\'"),
+            '"' => output.push_str("\\\""),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            c => xml::escape(output, c.encode_utf8(&mut [0; 4])),
+        }
+    }
+}
+
+fn write_text(output: &mut String, template: &str) {
+    let text = printf::from_template(template, 's', &mut escape_text);
+    if text.starts_with(['@', '?']) {
+        output.push('\\');
+    }
+    // whitespace is collapsed outside of quotes
+    let quote = text.starts_with(' ') || text.ends_with(' ') || text.contains("  ");
+    if quote {
+        output.push('"');
+    }
+    output.push_str(&text);
+    if quote {
+        output.push('"');
+    }
+}
+
+fn read_text(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut quoted = false;
+    let mut space = false;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '"' {
+            quoted = !quoted;
+            continue;
+        }
+        if c.is_whitespace() && !quoted {
+            space = true;
+            continue;
+        }
+        if space && !output.is_empty() {
+            output.push(' ');
+        }
+        space = false;
+
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => output.push('\n'),
+                Some('t') => output.push('\t'),
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    if let Some(c) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                        output.push(c);
+                    }
+                }
+                Some(c) => output.push(c),
+                None => {}
+            },
+            c => output.push(c),
+        }
+    }
+    printf::to_template(&output)
+}
+
+fn write_comments(output: &mut String, message: &Message) {
+    for comment in &message.comments {
+        output.push_str("    ");
+        xml::write_comment(output, comment);
+        output.push('\n');
+    }
+}
+
+fn write_name(output: &mut String, message: &Message) {
+    output.push_str(" name=\"");
+    xml::escape_attribute(output, &message.id);
+    output.push('"');
+    if let Some(context) = &message.context {
+        output.push_str(" i18n:context=\"");
+        xml::escape_attribute(output, context);
+        output.push('"');
+    }
+}
+
+pub(super) fn write<W: Write>(catalog: &Catalog, output: &mut W) -> core::fmt::Result {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<resources");
+    if catalog.messages().any(|m| m.context.is_some()) {
+        let _ = write!(xml, " xmlns:i18n=\"{NAMESPACE}\"");
+    }
+    xml.push_str(">\n");
+
+    let quantities = quantities(catalog.nplurals().unwrap_or(2));
+    for message in catalog.messages() {
+        write_comments(&mut xml, message);
+        if message.id_plural.is_some() {
+            xml.push_str("    <plurals");
+            write_name(&mut xml, message);
+            xml.push_str(">\n");
+            for (n, quantity) in quantities.iter().enumerate() {
+                let _ = write!(xml, "        <item quantity=\"{quantity}\">");
+                write_text(&mut xml, message.translations.get(n).map_or("", |t| t.as_str()));
+                xml.push_str("</item>\n");
+            }
+            xml.push_str("    </plurals>\n");
+        } else {
+            xml.push_str("    <string");
+            write_name(&mut xml, message);
+            xml.push('>');
+            write_text(&mut xml, message.translations.first().map_or("", |t| t.as_str()));
+            xml.push_str("</string>\n");
+        }
+    }
+    xml.push_str("</resources>\n");
+
+    output.write_str(&xml)
+}
+
+fn message(attributes: &[(&str, String)], comments: &mut Vec<CompactString>) -> Result<Message, InvalidFormat> {
+    Ok(Message {
+        context: attribute(attributes, "i18n:context").map(Into::into),
+        id: attribute(attributes, "name").ok_or(InvalidFormat)?.into(),
+        comments: core::mem::take(comments),
+        ..Message::default()
+    })
+}
+
+pub(super) fn parse(input: &str) -> Result<Catalog, InvalidFormat> {
+    let mut reader = Reader::new(input);
+    let mut catalog = Catalog::new();
+    let mut comments = Vec::new();
+
+    while let Some(event) = reader.next()? {
+        match event {
+            Event::Comment(comment) => comments.extend(comment.lines().map(|line| CompactString::from(line.trim()))),
+            Event::Start { name: "resources", .. } => {}
+            Event::Start {
+                name: "string",
+                attributes,
+                empty,
+            } => {
+                let mut message = message(&attributes, &mut comments)?;
+                let text = if empty { String::new() } else { reader.text()? };
+                message.translations.push(read_text(&text).into());
+                catalog.insert(message);
+            }
+            Event::Start {
+                name: "plurals",
+                attributes,
+                empty: false,
+            } => {
+                let mut message = message(&attributes, &mut comments)?;
+                let mut items = Vec::new();
+                loop {
+                    match reader.next()?.ok_or(InvalidFormat)? {
+                        Event::Start {
+                            name: "item",
+                            attributes,
+                            empty,
+                        } => {
+                            let quantity = attribute(&attributes, "quantity").ok_or(InvalidFormat)?;
+                            let category = CATEGORIES.iter().position(|c| *c == quantity).ok_or(InvalidFormat)?;
+                            let text = if empty { String::new() } else { reader.text()? };
+                            items.push((category, read_text(&text)));
+                        }
+                        Event::End("plurals") => break,
+                        Event::Text(text) if text.trim().is_empty() => {}
+                        Event::Comment(_) => {}
+                        _ => return Err(InvalidFormat),
+                    }
+                }
+                items.sort_by_key(|(category, _)| *category);
+                message.id_plural = Some(message.id.clone());
+                message.translations = items.into_iter().map(|(_, text)| text.into()).collect();
+                catalog.insert(message);
+            }
+            Event::Start { empty, .. } => {
+                // other resources, e.g. string arrays, are skipped
+                comments.clear();
+                if !empty {
+                    reader.text()?;
+                }
+            }
+            Event::End(_) | Event::Text(_) => {}
+        }
+    }
+
+    Ok(catalog)
+}
@@ -0,0 +1,189 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use compact_str::CompactString;
+
+use super::{Catalog, MAX_PLURAL_FORMS, Message, printf};
+use crate::InvalidFormat;
+
+fn escape(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            c if c.is_control() => {
+                let _ = write!(output, "\\U{:04X}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+}
+
+fn write_entry(output: &mut String, key: &str, template: &str) {
+    output.push('"');
+    escape(output, key);
+    output.push_str("\" = \"");
+    output.push_str(&printf::from_template(template, '@', &mut escape));
+    output.push_str("\";\n");
+}
+
+pub(super) fn write<W: Write>(catalog: &Catalog, output: &mut W) -> core::fmt::Result {
+    let mut strings = String::new();
+    for message in catalog.messages() {
+        if !strings.is_empty() {
+            strings.push('\n');
+        }
+        if !message.comments.is_empty() {
+            let _ = writeln!(strings, "/* {} */", message.comments.join("\n").replace("*/", "* /"));
+        }
+
+        let key = super::key(message.context.as_deref(), &message.id);
+        if message.id_plural.is_some() {
+            for n in 0..message.translations.len().max(2) {
+                let translation = message.translations.get(n).map_or("", |t| t.as_str());
+                write_entry(&mut strings, &alloc::format!("{key}[{n}]"), translation);
+            }
+        } else {
+            write_entry(&mut strings, &key, message.translations.first().map_or("", |t| t.as_str()));
+        }
+    }
+    output.write_str(&strings)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    comments: Vec<CompactString>,
+}
+
+impl Parser<'_> {
+    /// Skip whitespace and comments, collecting the comments.
+    fn skip(&mut self) -> Result<(), InvalidFormat> {
+        loop {
+            let rest = &self.input[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if let Some(comment) = trimmed.strip_prefix("/*") {
+                let end = comment.find("*/").ok_or(InvalidFormat)?;
+                self.comments
+                    .extend(comment[..end].lines().map(|line| CompactString::from(line.trim())));
+                self.pos += end + 4;
+            } else if let Some(comment) = trimmed.strip_prefix("//") {
+                let end = comment.find('\n').unwrap_or(comment.len());
+                self.comments.push(comment[..end].trim().into());
+                self.pos += end + 2;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), InvalidFormat> {
+        self.skip()?;
+        if !self.input[self.pos..].starts_with(c) {
+            return Err(InvalidFormat);
+        }
+        self.pos += c.len_utf8();
+        Ok(())
+    }
+
+    fn string(&mut self) -> Result<String, InvalidFormat> {
+        self.skip()?;
+        let rest = &self.input[self.pos..];
+        let Some(quoted) = rest.strip_prefix('"') else {
+            // unquoted strings of old style property lists
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '$' | '/' | ':' | '-')))
+                .unwrap_or(rest.len());
+            if len == 0 {
+                return Err(InvalidFormat);
+            }
+            self.pos += len;
+            return Ok(rest[..len].into());
+        };
+
+        let mut output = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((n, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += n + 2;
+                    return Ok(output);
+                }
+                '\\' => match chars.next().ok_or(InvalidFormat)?.1 {
+                    'n' => output.push('\n'),
+                    't' => output.push('\t'),
+                    'r' => output.push('\r'),
+                    'U' | 'u' => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let code = u32::from_str_radix(&hex, 16).map_err(|_| InvalidFormat)?;
+                        output.push(char::from_u32(code).ok_or(InvalidFormat)?);
+                    }
+                    c => output.push(c),
+                },
+                c => output.push(c),
+            }
+        }
+        Err(InvalidFormat)
+    }
+}
+
+/// Split the plural form index of a key, e.g. `files[1]`.
+fn plural_form(key: &str) -> Option<(&str, usize)> {
+    let (key, n) = key.strip_suffix(']')?.rsplit_once('[')?;
+    Some((key, n.parse().ok()?))
+}
+
+pub(super) fn parse(input: &str) -> Result<Catalog, InvalidFormat> {
+    let mut parser = Parser {
+        input: input.strip_prefix('\u{feff}').unwrap_or(input),
+        pos: 0,
+        comments: Vec::new(),
+    };
+    let mut catalog = Catalog::new();
+
+    loop {
+        parser.skip()?;
+        if parser.pos == parser.input.len() {
+            break;
+        }
+
+        let key = parser.string()?;
+        parser.expect('=')?;
+        let value = printf::to_template(&parser.string()?);
+        parser.expect(';')?;
+
+        let (key, form) = match plural_form(&key) {
+            Some((key, form)) => (key, Some(form)),
+            None => (key.as_str(), None),
+        };
+        let (context, id) = match key.split_once('\x04') {
+            Some((context, id)) => (Some(context), id),
+            None => (None, key),
+        };
+        let mut message = match catalog.get(context, id) {
+            Some(message) if form.is_some() => message.clone(),
+            _ => Message {
+                context: context.map(Into::into),
+                id: id.into(),
+                id_plural: form.map(|_| id.into()),
+                ..Message::default()
+            },
+        };
+        message.comments.append(&mut parser.comments);
+        let form = form.unwrap_or(0);
+        if form >= MAX_PLURAL_FORMS {
+            return Err(InvalidFormat);
+        }
+        if message.translations.len() <= form {
+            message.translations.resize(form + 1, CompactString::default());
+        }
+        message.translations[form] = value.into();
+        catalog.insert(message);
+    }
+
+    Ok(catalog)
+}
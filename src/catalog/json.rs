@@ -0,0 +1,122 @@
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use compact_str::CompactString;
+
+use super::{Catalog, Message};
+use crate::{
+    InvalidFormat,
+    json::{self, Value},
+};
+
+fn write_strings<W: Write>(output: &mut W, key: &str, values: &[CompactString]) -> core::fmt::Result {
+    write!(output, ",\n      \"{key}\": [")?;
+    for (n, value) in values.iter().enumerate() {
+        if n > 0 {
+            output.write_str(", ")?;
+        }
+        json::write_string(output, value)?;
+    }
+    output.write_char(']')
+}
+
+pub(super) fn write<W: Write>(catalog: &Catalog, output: &mut W) -> core::fmt::Result {
+    output.write_str("{\n")?;
+    if !catalog.header().is_empty() {
+        output.write_str("  \"header\": ")?;
+        json::write_string(output, catalog.header())?;
+        output.write_str(",\n")?;
+    }
+    output.write_str("  \"messages\": [")?;
+    for (n, message) in catalog.messages().enumerate() {
+        output.write_str(if n > 0 { ",\n    {\n" } else { "\n    {\n" })?;
+        if let Some(context) = &message.context {
+            output.write_str("      \"context\": ")?;
+            json::write_string(output, context)?;
+            output.write_str(",\n")?;
+        }
+        output.write_str("      \"id\": ")?;
+        json::write_string(output, &message.id)?;
+        if let Some(id_plural) = &message.id_plural {
+            output.write_str(",\n      \"id_plural\": ")?;
+            json::write_string(output, id_plural)?;
+        }
+        write_strings(output, "translations", &message.translations)?;
+        if !message.comments.is_empty() {
+            write_strings(output, "comments", &message.comments)?;
+        }
+        if message.fuzzy {
+            output.write_str(",\n      \"fuzzy\": true")?;
+        }
+        output.write_str("\n    }")?;
+    }
+    if catalog.is_empty() {
+        output.write_str("]\n}\n")
+    } else {
+        output.write_str("\n  ]\n}\n")
+    }
+}
+
+fn string(value: Value) -> Result<CompactString, InvalidFormat> {
+    match value {
+        Value::String(s) => Ok(s.into()),
+        _ => Err(InvalidFormat),
+    }
+}
+
+fn strings(value: Value) -> Result<Vec<CompactString>, InvalidFormat> {
+    match value {
+        Value::Array(values) => values.into_iter().map(string).collect(),
+        _ => Err(InvalidFormat),
+    }
+}
+
+fn message(value: Value) -> Result<Message, InvalidFormat> {
+    let Value::Object(members) = value else {
+        return Err(InvalidFormat);
+    };
+
+    let mut message = Message::default();
+    let mut has_id = false;
+    for (key, value) in members {
+        match key.as_str() {
+            "context" => message.context = Some(string(value)?),
+            "id" => {
+                message.id = string(value)?;
+                has_id = true;
+            }
+            "id_plural" => message.id_plural = Some(string(value)?),
+            "translations" => message.translations = strings(value)?,
+            "comments" => message.comments = strings(value)?,
+            "fuzzy" => match value {
+                Value::Bool(fuzzy) => message.fuzzy = fuzzy,
+                _ => return Err(InvalidFormat),
+            },
+            _ => return Err(InvalidFormat),
+        }
+    }
+    if !has_id {
+        return Err(InvalidFormat);
+    }
+    Ok(message)
+}
+
+pub(super) fn parse(input: &str) -> Result<Catalog, InvalidFormat> {
+    let Value::Object(members) = json::parse(input)? else {
+        return Err(InvalidFormat);
+    };
+
+    let mut catalog = Catalog::new();
+    for (key, value) in members {
+        match (key.as_str(), value) {
+            ("header", value) => catalog.set_header(string(value)?),
+            ("messages", Value::Array(messages)) => {
+                for value in messages {
+                    catalog.insert(message(value)?);
+                }
+            }
+            _ => return Err(InvalidFormat),
+        }
+    }
+    Ok(catalog)
+}
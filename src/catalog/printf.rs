@@ -0,0 +1,118 @@
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::template::{self, Align, Segment};
+
+/// Convert a template to printf style placeholders, `{0}` becomes `%1$s` with `conversion` as conversion character.
+///
+/// Widths and precisions are kept, centered alignment and custom fills have no printf equivalent and are dropped.
+/// `escape` writes text in the syntax of the target format, after `%` has been doubled.
+pub(super) fn from_template(template: &str, conversion: char, escape: &mut dyn FnMut(&mut String, &str)) -> String {
+    let mut output = String::new();
+    for segment in template::segments(template) {
+        match segment {
+            Segment::Text(range) | Segment::Invalid(range) => escape(&mut output, &template[range].replace('%', "%%")),
+            Segment::Argument { index, spec, .. } => {
+                let _ = write!(output, "%{}$", index + 1);
+                if let Some(spec) = spec {
+                    if let Some(width) = spec.width {
+                        if spec.align != Some(Align::Right) {
+                            output.push('-');
+                        }
+                        let _ = write!(output, "{width}");
+                    }
+                    if let Some(precision) = spec.precision {
+                        let _ = write!(output, ".{precision}");
+                    }
+                }
+                output.push(conversion);
+            }
+        }
+    }
+    output
+}
+
+fn digits(s: &str) -> (Option<usize>, &str) {
+    let len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    (s[..len].parse().ok(), &s[len..])
+}
+
+/// Parse a printf directive after `%`, returns the placeholder and the rest.
+fn directive<'a>(s: &'a str, next: &mut usize) -> Option<(String, &'a str)> {
+    let (position, rest) = match digits(s) {
+        (Some(position), rest) if rest.starts_with('$') && position > 0 => (Some(position - 1), &rest[1..]),
+        _ => (None, s),
+    };
+
+    let flags = rest.len() - rest.trim_start_matches(['-', '+', ' ', '0', '#']).len();
+    let left = rest[..flags].contains('-');
+    let (width, rest) = digits(&rest[flags..]);
+    let (precision, rest) = match rest.strip_prefix('.') {
+        Some(rest) => {
+            let (precision, rest) = digits(rest);
+            (Some(precision.unwrap_or(0)), rest)
+        }
+        None => (None, rest),
+    };
+    let rest = rest.trim_start_matches(['h', 'l', 'L', 'q', 'j', 'z', 't']);
+    let conversion = rest.chars().next()?;
+    if !"s@dDiuUxXoOfFeEgGaAcCp".contains(conversion) {
+        return None;
+    }
+
+    let index = position.unwrap_or(*next);
+    *next = index + 1;
+    let mut placeholder = String::new();
+    let _ = write!(placeholder, "{{{index}");
+    if width.is_some() || precision.is_some() {
+        placeholder.push(':');
+        if let Some(width) = width {
+            let _ = write!(placeholder, "{}{width}", if left { '<' } else { '>' });
+        }
+        if let Some(precision) = precision {
+            let _ = write!(placeholder, ".{precision}");
+        }
+    }
+    placeholder.push('}');
+    Some((placeholder, &rest[conversion.len_utf8()..]))
+}
+
+/// Convert printf style text to a template, `%1$s`, `%@` and `%d` become `{0}` and braces are escaped.
+///
+/// Sequential directives are numbered after the previous one, invalid directives are kept as text.
+pub(super) fn to_template(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut next = 0;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        match c {
+            '%' if rest.starts_with('%') => {
+                output.push('%');
+                rest = &rest[1..];
+            }
+            '%' => match directive(rest, &mut next) {
+                Some((placeholder, after)) => {
+                    output.push_str(&placeholder);
+                    rest = after;
+                }
+                None => output.push('%'),
+            },
+            '{' => output.push_str("{{"),
+            '}' => output.push_str("}}"),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+/// Escape braces of plain text so it can be used as a template.
+pub(super) fn escape_braces(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '{' => output.push_str("{{"),
+            '}' => output.push_str("}}"),
+            c => output.push(c),
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use compact_str::CompactString;
+
+use super::{Catalog, Message};
+use crate::{InvalidFormat, json};
+
+// basic strings of TOML share the escapes of JSON strings
+fn write_strings<W: Write>(output: &mut W, key: &str, values: &[CompactString]) -> core::fmt::Result {
+    write!(output, "{key} = [")?;
+    for (n, value) in values.iter().enumerate() {
+        if n > 0 {
+            output.write_str(", ")?;
+        }
+        json::write_string(output, value)?;
+    }
+    output.write_str("]\n")
+}
+
+pub(super) fn write<W: Write>(catalog: &Catalog, output: &mut W) -> core::fmt::Result {
+    let mut first = true;
+    if !catalog.header().is_empty() {
+        output.write_str("header = ")?;
+        json::write_string(output, catalog.header())?;
+        output.write_char('\n')?;
+        first = false;
+    }
+
+    for message in catalog.messages() {
+        if !first {
+            output.write_char('\n')?;
+        }
+        first = false;
+
+        output.write_str("[[messages]]\n")?;
+        if let Some(context) = &message.context {
+            output.write_str("context = ")?;
+            json::write_string(output, context)?;
+            output.write_char('\n')?;
+        }
+        output.write_str("id = ")?;
+        json::write_string(output, &message.id)?;
+        output.write_char('\n')?;
+        if let Some(id_plural) = &message.id_plural {
+            output.write_str("id_plural = ")?;
+            json::write_string(output, id_plural)?;
+            output.write_char('\n')?;
+        }
+        write_strings(output, "translations", &message.translations)?;
+        if !message.comments.is_empty() {
+            write_strings(output, "comments", &message.comments)?;
+        }
+        if message.fuzzy {
+            output.write_str("fuzzy = true\n")?;
+        }
+    }
+    Ok(())
+}
+
+enum Value {
+    String(String),
+    Array(Vec<Value>),
+    Bool(bool),
+}
+
+/// A parser for the subset of TOML written by [`write`]: string, string array and boolean keys in the root table
+/// and in `[[messages]]` tables.
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    /// Skip whitespace, and line breaks and comments if `lines` is set.
+    fn skip(&mut self, lines: bool) {
+        loop {
+            let rest = self.rest();
+            let trimmed = if lines {
+                rest.trim_start()
+            } else {
+                rest.trim_start_matches([' ', '\t'])
+            };
+            self.pos += rest.len() - trimmed.len();
+            match trimmed.strip_prefix('#') {
+                Some(comment) if lines => self.pos += 1 + comment.find('\n').unwrap_or(comment.len()),
+                _ => return,
+            }
+        }
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn end_of_line(&mut self) -> Result<(), InvalidFormat> {
+        self.skip(false);
+        let rest = self.rest();
+        if rest.is_empty() || rest.starts_with(['\n', '\r', '#']) {
+            Ok(())
+        } else {
+            Err(InvalidFormat)
+        }
+    }
+
+    fn hex(&mut self, len: usize) -> Result<char, InvalidFormat> {
+        let hex = self.rest().get(..len).ok_or(InvalidFormat)?;
+        let c = u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(InvalidFormat)?;
+        self.pos += len;
+        Ok(c)
+    }
+
+    fn string(&mut self) -> Result<String, InvalidFormat> {
+        if self.eat("'") {
+            let rest = self.rest();
+            let end = rest.find(['\'', '\n']).ok_or(InvalidFormat)?;
+            if !rest[end..].starts_with('\'') {
+                return Err(InvalidFormat);
+            }
+            let s = rest[..end].into();
+            self.pos += end + 1;
+            return Ok(s);
+        }
+
+        if !self.eat("\"") {
+            return Err(InvalidFormat);
+        }
+        let mut output = String::new();
+        loop {
+            let c = self.rest().chars().next().ok_or(InvalidFormat)?;
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(output),
+                '\\' => {
+                    let c = self.rest().chars().next().ok_or(InvalidFormat)?;
+                    self.pos += c.len_utf8();
+                    match c {
+                        '"' | '\\' => output.push(c),
+                        'b' => output.push('\x08'),
+                        'f' => output.push('\x0c'),
+                        'n' => output.push('\n'),
+                        'r' => output.push('\r'),
+                        't' => output.push('\t'),
+                        'u' => output.push(self.hex(4)?),
+                        'U' => output.push(self.hex(8)?),
+                        _ => return Err(InvalidFormat),
+                    }
+                }
+                '\n' => return Err(InvalidFormat),
+                c => output.push(c),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, InvalidFormat> {
+        if self.eat("true") {
+            Ok(Value::Bool(true))
+        } else if self.eat("false") {
+            Ok(Value::Bool(false))
+        } else if self.eat("[") {
+            let mut values = Vec::new();
+            loop {
+                self.skip(true);
+                if self.eat("]") {
+                    return Ok(Value::Array(values));
+                }
+                // nested arrays are not part of the catalog schema
+                values.push(Value::String(self.string()?));
+                self.skip(true);
+                if !self.eat(",") {
+                    self.skip(true);
+                    if !self.eat("]") {
+                        return Err(InvalidFormat);
+                    }
+                    return Ok(Value::Array(values));
+                }
+            }
+        } else {
+            self.string().map(Value::String)
+        }
+    }
+
+    fn key(&mut self) -> Result<String, InvalidFormat> {
+        if self.rest().starts_with(['"', '\'']) {
+            return self.string();
+        }
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-')))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(InvalidFormat);
+        }
+        let key = rest[..len].into();
+        self.pos += len;
+        Ok(key)
+    }
+}
+
+fn string(value: Value) -> Result<CompactString, InvalidFormat> {
+    match value {
+        Value::String(s) => Ok(s.into()),
+        _ => Err(InvalidFormat),
+    }
+}
+
+fn strings(value: Value) -> Result<Vec<CompactString>, InvalidFormat> {
+    match value {
+        Value::Array(values) => values.into_iter().map(string).collect(),
+        _ => Err(InvalidFormat),
+    }
+}
+
+pub(super) fn parse(input: &str) -> Result<Catalog, InvalidFormat> {
+    let mut parser = Parser { input, pos: 0 };
+    let mut catalog = Catalog::new();
+    let mut message: Option<Message> = None;
+
+    loop {
+        parser.skip(true);
+        if parser.rest().is_empty() {
+            break;
+        }
+
+        if parser.eat("[[") {
+            parser.skip(false);
+            if parser.key()? != "messages" {
+                return Err(InvalidFormat);
+            }
+            parser.skip(false);
+            if !parser.eat("]]") {
+                return Err(InvalidFormat);
+            }
+            parser.end_of_line()?;
+            catalog.extend(message.replace(Message::default()));
+            continue;
+        }
+
+        let key = parser.key()?;
+        parser.skip(false);
+        if !parser.eat("=") {
+            return Err(InvalidFormat);
+        }
+        parser.skip(false);
+        let value = parser.value()?;
+        parser.end_of_line()?;
+
+        match (&mut message, key.as_str()) {
+            (None, "header") => catalog.set_header(string(value)?),
+            (Some(message), "context") => message.context = Some(string(value)?),
+            (Some(message), "id") => message.id = string(value)?,
+            (Some(message), "id_plural") => message.id_plural = Some(string(value)?),
+            (Some(message), "translations") => message.translations = strings(value)?,
+            (Some(message), "comments") => message.comments = strings(value)?,
+            (Some(message), "fuzzy") => match value {
+                Value::Bool(fuzzy) => message.fuzzy = fuzzy,
+                _ => return Err(InvalidFormat),
+            },
+            _ => return Err(InvalidFormat),
+        }
+    }
+    catalog.extend(message);
+
+    Ok(catalog)
+}
@@ -0,0 +1,267 @@
+use alloc::string::String;
+use core::fmt::Write;
+
+use compact_str::CompactString;
+
+use super::{
+    Catalog, Message, printf,
+    xml::{self, Event, Reader, attribute},
+};
+use crate::{
+    InvalidFormat,
+    template::{self, Segment},
+};
+
+/// Context type of the gettext context of a unit.
+const CONTEXT_TYPE: &str = "x-gettext-msgctxt";
+
+/// Resource type of a group holding the plural forms of a message.
+const PLURALS: &str = "x-gettext-plurals";
+
+/// Write a template as inline content, placeholders become `<x/>` elements with the placeholder as equivalent text.
+fn write_content(output: &mut String, template: &str) {
+    for segment in template::segments(template) {
+        match segment {
+            Segment::Text(range) | Segment::Invalid(range) => xml::escape(output, &template[range]),
+            Segment::Argument { index, raw, .. } => {
+                let _ = write!(output, "<x id=\"{index}\" equiv-text=\"");
+                xml::escape_attribute(output, &template[raw]);
+                output.push_str("\"/>");
+            }
+        }
+    }
+}
+
+fn write_unit(output: &mut String, indent: &str, id: &str, source: &str, translation: &str, fuzzy: bool) {
+    let _ = write!(output, "{indent}<trans-unit id=\"{id}\">\n{indent}  <source>");
+    write_content(output, source);
+    output.push_str("</source>\n");
+    if !translation.is_empty() {
+        let _ = write!(output, "{indent}  <target");
+        if fuzzy {
+            output.push_str(" state=\"needs-review-translation\"");
+        }
+        output.push('>');
+        write_content(output, translation);
+        output.push_str("</target>\n");
+    }
+}
+
+fn write_annotations(output: &mut String, indent: &str, message: &Message) {
+    if let Some(context) = &message.context {
+        let _ = write!(
+            output,
+            "{indent}<context-group purpose=\"information\">\n{indent}  <context context-type=\"{CONTEXT_TYPE}\">"
+        );
+        xml::escape(output, context);
+        let _ = writeln!(output, "</context>\n{indent}</context-group>");
+    }
+    for comment in &message.comments {
+        let _ = write!(output, "{indent}<note>");
+        xml::escape(output, comment);
+        output.push_str("</note>\n");
+    }
+}
+
+pub(super) fn write<W: Write>(catalog: &Catalog, output: &mut W) -> core::fmt::Result {
+    let mut xliff = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n  \
+         <file original=\"messages\" datatype=\"plaintext\" source-language=\"",
+    );
+    xml::escape_attribute(&mut xliff, catalog.header_field("X-Source-Language").unwrap_or("en"));
+    xliff.push('"');
+    if let Some(language) = catalog.header_field("Language").filter(|l| !l.is_empty()) {
+        xliff.push_str(" target-language=\"");
+        xml::escape_attribute(&mut xliff, language);
+        xliff.push('"');
+    }
+    xliff.push_str(">\n");
+    if !catalog.header().is_empty() {
+        xliff.push_str("    <header>\n      <note>");
+        xml::escape(&mut xliff, catalog.header());
+        xliff.push_str("</note>\n    </header>\n");
+    }
+    xliff.push_str("    <body>\n");
+
+    for (n, message) in catalog.messages().enumerate() {
+        let id = n + 1;
+        match &message.id_plural {
+            Some(id_plural) => {
+                let _ = writeln!(xliff, "      <group id=\"{id}\" restype=\"{PLURALS}\">");
+                write_annotations(&mut xliff, "        ", message);
+                for form in 0..message.translations.len().max(2) {
+                    let source = if form == 0 { &message.id } else { id_plural };
+                    let translation = message.translations.get(form).map_or("", |t| t.as_str());
+                    write_unit(
+                        &mut xliff,
+                        "        ",
+                        &alloc::format!("{id}[{form}]"),
+                        source,
+                        translation,
+                        message.fuzzy,
+                    );
+                    xliff.push_str("        </trans-unit>\n");
+                }
+                xliff.push_str("      </group>\n");
+            }
+            None => {
+                let translation = message.translations.first().map_or("", |t| t.as_str());
+                write_unit(
+                    &mut xliff,
+                    "      ",
+                    &alloc::format!("{id}"),
+                    &message.id,
+                    translation,
+                    message.fuzzy,
+                );
+                write_annotations(&mut xliff, "        ", message);
+                xliff.push_str("      </trans-unit>\n");
+            }
+        }
+    }
+
+    xliff.push_str("    </body>\n  </file>\n</xliff>\n");
+    output.write_str(&xliff)
+}
+
+/// Read inline content up to the end of the current element as a template.
+fn read_content(reader: &mut Reader<'_>) -> Result<String, InvalidFormat> {
+    let mut output = String::new();
+    let mut depth = 0usize;
+    loop {
+        match reader.next()?.ok_or(InvalidFormat)? {
+            Event::Text(text) => printf::escape_braces(&mut output, &text),
+            Event::Start {
+                name: "x" | "ph",
+                attributes,
+                empty,
+            } => {
+                let placeholder = attribute(&attributes, "equiv-text")
+                    .filter(|text| matches!(template::segments(text).next(), Some(Segment::Argument { raw, .. }) if raw.len() == text.len()));
+                match placeholder {
+                    Some(placeholder) => output.push_str(placeholder),
+                    None => {
+                        let index: usize = attribute(&attributes, "id")
+                            .and_then(|id| id.parse().ok())
+                            .ok_or(InvalidFormat)?;
+                        let _ = write!(output, "{{{index}}}");
+                    }
+                }
+                if !empty {
+                    // the content of a native placeholder is replaced by the template placeholder
+                    reader.text()?;
+                }
+            }
+            Event::Start { empty: false, .. } => depth += 1,
+            Event::End(_) if depth == 0 => return Ok(output),
+            Event::End(_) => depth -= 1,
+            Event::Start { .. } | Event::Comment(_) => {}
+        }
+    }
+}
+
+#[derive(Default)]
+struct Unit {
+    source: Option<String>,
+    target: Option<String>,
+    fuzzy: bool,
+}
+
+pub(super) fn parse(input: &str) -> Result<Catalog, InvalidFormat> {
+    let mut reader = Reader::new(input);
+    let mut catalog = Catalog::new();
+
+    // message of the current unit or plural group, and whether it is a plural group
+    let mut message: Option<(Message, bool)> = None;
+    let mut unit: Option<Unit> = None;
+    let mut in_header = false;
+
+    while let Some(event) = reader.next()? {
+        match event {
+            Event::Start {
+                name: "header", empty, ..
+            } => in_header = !empty,
+            Event::End("header") => in_header = false,
+            Event::Start { name: "note", empty, .. } => {
+                let note = if empty { String::new() } else { reader.text()? };
+                match &mut message {
+                    _ if in_header => catalog.set_header(note),
+                    Some((message, _)) => message.comments.extend(note.lines().map(CompactString::from)),
+                    None => {}
+                }
+            }
+            Event::Start {
+                name: "group",
+                attributes,
+                empty: false,
+            } if attribute(&attributes, "restype") == Some(PLURALS) => {
+                message = Some((Message::default(), true));
+            }
+            Event::End("group") => {
+                if let Some((message, true)) = message.take() {
+                    catalog.insert(message);
+                }
+            }
+            Event::Start {
+                name: "trans-unit",
+                empty: false,
+                ..
+            } => {
+                if message.is_none() {
+                    message = Some((Message::default(), false));
+                }
+                unit = Some(Unit::default());
+            }
+            Event::Start {
+                name: "source", empty, ..
+            } => {
+                let content = if empty { String::new() } else { read_content(&mut reader)? };
+                if let Some(unit) = &mut unit {
+                    unit.source = Some(content);
+                }
+            }
+            Event::Start {
+                name: "target",
+                attributes,
+                empty,
+            } => {
+                let content = if empty { String::new() } else { read_content(&mut reader)? };
+                if let Some(unit) = &mut unit {
+                    unit.target = Some(content);
+                    unit.fuzzy = attribute(&attributes, "state").is_some_and(|state| state.starts_with("needs-review"));
+                }
+            }
+            Event::Start {
+                name: "context",
+                attributes,
+                empty,
+            } => {
+                let context = if empty { String::new() } else { reader.text()? };
+                if attribute(&attributes, "context-type") == Some(CONTEXT_TYPE)
+                    && let Some((message, _)) = &mut message
+                {
+                    message.context = Some(context.into());
+                }
+            }
+            Event::End("trans-unit") => {
+                let unit = unit.take().ok_or(InvalidFormat)?;
+                let (current, plural) = message.as_mut().ok_or(InvalidFormat)?;
+                let source = CompactString::from(unit.source.ok_or(InvalidFormat)?);
+                match current.translations.len() {
+                    0 => current.id = source,
+                    1 if *plural => current.id_plural = Some(source),
+                    _ => {}
+                }
+                current.translations.push(unit.target.unwrap_or_default().into());
+                current.fuzzy |= unit.fuzzy;
+                if !*plural && let Some((message, _)) = message.take() {
+                    catalog.insert(message);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(catalog)
+}
@@ -0,0 +1,221 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::InvalidFormat;
+
+/// An event of [`Reader`], names are kept with their namespace prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Event<'a> {
+    Start {
+        name: &'a str,
+        attributes: Vec<(&'a str, String)>,
+        empty: bool,
+    },
+    End(&'a str),
+    Text(String),
+    Comment(&'a str),
+}
+
+/// Get the value of an attribute of a start event.
+pub(super) fn attribute<'a>(attributes: &'a [(&str, String)], name: &str) -> Option<&'a str> {
+    attributes.iter().find(|(n, _)| *n == name).map(|(_, v)| v.as_str())
+}
+
+/// A pull parser for the subset of XML used by catalog formats.
+///
+/// Declarations, processing instructions and doctypes are skipped, CDATA sections are reported as text.
+pub(super) struct Reader<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+fn unescape(s: &str) -> Result<String, InvalidFormat> {
+    let mut output = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(n) = rest.find('&') {
+        output.push_str(&rest[..n]);
+        let end = rest[n..].find(';').ok_or(InvalidFormat)? + n;
+        let c = match &rest[n + 1..end] {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            entity => {
+                let code = match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16),
+                    None => entity.strip_prefix('#').ok_or(InvalidFormat)?.parse(),
+                };
+                code.ok().and_then(char::from_u32).ok_or(InvalidFormat)?
+            }
+        };
+        output.push(c);
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')
+}
+
+impl<'a> Reader<'a> {
+    pub(super) fn new(input: &'a str) -> Self {
+        Self {
+            input: input.strip_prefix('\u{feff}').unwrap_or(input),
+            pos: 0,
+        }
+    }
+
+    fn skip_until(&mut self, end: &str) -> Result<&'a str, InvalidFormat> {
+        let rest = &self.input[self.pos..];
+        let n = rest.find(end).ok_or(InvalidFormat)?;
+        self.pos += n + end.len();
+        Ok(&rest[..n])
+    }
+
+    fn name(&mut self) -> Result<&'a str, InvalidFormat> {
+        let rest = &self.input[self.pos..];
+        let len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(InvalidFormat);
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Get the next event, `None` at the end of the input.
+    pub(super) fn next(&mut self) -> Result<Option<Event<'a>>, InvalidFormat> {
+        loop {
+            let rest = &self.input[self.pos..];
+            if rest.is_empty() {
+                return Ok(None);
+            }
+
+            if !rest.starts_with('<') {
+                let len = rest.find('<').unwrap_or(rest.len());
+                self.pos += len;
+                return Ok(Some(Event::Text(unescape(&rest[..len])?)));
+            }
+
+            if rest.starts_with("<!--") {
+                self.pos += 4;
+                return Ok(Some(Event::Comment(self.skip_until("-->")?)));
+            }
+            if rest.starts_with("<![CDATA[") {
+                self.pos += 9;
+                return Ok(Some(Event::Text(self.skip_until("]]>")?.into())));
+            }
+            if rest.starts_with("<?") {
+                self.skip_until("?>")?;
+                continue;
+            }
+            if rest.starts_with("<!") {
+                self.skip_until(">")?;
+                continue;
+            }
+
+            self.pos += 1;
+            if rest.starts_with("</") {
+                self.pos += 1;
+                let name = self.name()?;
+                self.skip_whitespace();
+                if !self.input[self.pos..].starts_with('>') {
+                    return Err(InvalidFormat);
+                }
+                self.pos += 1;
+                return Ok(Some(Event::End(name)));
+            }
+
+            let name = self.name()?;
+            let mut attributes = Vec::new();
+            loop {
+                self.skip_whitespace();
+                let rest = &self.input[self.pos..];
+                if let Some(after) = rest.strip_prefix("/>") {
+                    self.pos = self.input.len() - after.len();
+                    return Ok(Some(Event::Start {
+                        name,
+                        attributes,
+                        empty: true,
+                    }));
+                }
+                if rest.starts_with('>') {
+                    self.pos += 1;
+                    return Ok(Some(Event::Start {
+                        name,
+                        attributes,
+                        empty: false,
+                    }));
+                }
+
+                let attribute = self.name()?;
+                self.skip_whitespace();
+                if !self.input[self.pos..].starts_with('=') {
+                    return Err(InvalidFormat);
+                }
+                self.pos += 1;
+                self.skip_whitespace();
+                let quote = self.input[self.pos..]
+                    .chars()
+                    .next()
+                    .filter(|c| matches!(c, '"' | '\''))
+                    .ok_or(InvalidFormat)?;
+                self.pos += 1;
+                let value = self.skip_until(if quote == '"' { "\"" } else { "'" })?;
+                attributes.push((attribute, unescape(value)?));
+            }
+        }
+    }
+
+    /// Read the text content up to the end of the current element, skipping nested tags and comments.
+    pub(super) fn text(&mut self) -> Result<String, InvalidFormat> {
+        let mut output = String::new();
+        let mut depth = 0usize;
+        loop {
+            match self.next()?.ok_or(InvalidFormat)? {
+                Event::Text(text) => output.push_str(&text),
+                Event::Start { empty: false, .. } => depth += 1,
+                Event::End(_) if depth == 0 => return Ok(output),
+                Event::End(_) => depth -= 1,
+                Event::Start { .. } | Event::Comment(_) => {}
+            }
+        }
+    }
+}
+
+/// Escape text for element content.
+pub(super) fn escape(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '&' => output.push_str("&amp;"),
+            c => output.push(c),
+        }
+    }
+}
+
+/// Escape text for a double quoted attribute value, line breaks are kept as character references.
+pub(super) fn escape_attribute(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("&quot;"),
+            '\n' => output.push_str("&#10;"),
+            '\t' => output.push_str("&#9;"),
+            c => escape(output, c.encode_utf8(&mut [0; 4])),
+        }
+    }
+}
+
+/// Write a comment, `--` is not allowed in comments and is split with a space.
+pub(super) fn write_comment(output: &mut String, text: &str) {
+    output.push_str("<!-- ");
+    output.push_str(&text.replace("--", "- -"));
+    output.push_str(" -->");
+}
@@ -1,81 +1,627 @@
 use alloc::{borrow::Cow, string::String, vec::Vec};
 
+use crate::InvalidFormat;
+
+/// Check whether a character is escaped in I18nString format: quotes, backslashes and control characters.
+fn needs_escape(c: char) -> bool {
+    c == '\'' || c == '\\' || c.is_control()
+}
+
+/// Write a character escaped in I18nString format.
+fn write_escaped_char<W: core::fmt::Write>(output: &mut W, c: char) -> core::fmt::Result {
+    write_escaped_char_in(output, c, '\'')
+}
+
+/// Write a character escaped in a literal delimited by `quote`.
+fn write_escaped_char_in<W: core::fmt::Write>(output: &mut W, c: char, quote: char) -> core::fmt::Result {
+    match c {
+        '\\' => output.write_str("\\\\"),
+        c if c == quote => {
+            output.write_char('\\')?;
+            output.write_char(c)
+        }
+        '\n' => output.write_str("\\n"),
+        '\t' => output.write_str("\\t"),
+        '\r' => output.write_str("\\r"),
+        '\0' => output.write_str("\\0"),
+        c if c.is_control() => write!(output, "\\u{{{:x}}}", c as u32),
+        c => output.write_char(c),
+    }
+}
+
 /// Escape special characters in a string to fit I18nString format.
+///
+/// Quotes and backslashes are escaped with a backslash, control characters as `\n`, `\t`, `\r`, `\0` or `\u{...}`.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::escape::escape;
+///
+/// assert_eq!(escape("it's\r\n\x1b"), "it\\'s\\r\\n\\u{1b}");
+/// assert_eq!(escape("plain"), "plain");
+/// ```
 pub fn escape(input: &str) -> Cow<'_, str> {
-    if !input.contains(['\'', '\\', '\n', '\t']) {
+    if !input.contains(needs_escape) {
         return Cow::Borrowed(input);
     }
 
-    let mut output = String::with_capacity(input.len());
+    let mut output = String::with_capacity(input.len() + 8);
     for c in input.chars() {
-        match c {
-            '\'' => output.push_str("\\'"),
-            '\\' => output.push_str("\\\\"),
-            '\n' => output.push_str("\\n"),
-            '\t' => output.push_str("\\t"),
-            _ => output.push(c),
-        }
+        let _ = write_escaped_char(&mut output, c);
     }
     Cow::Owned(output)
 }
 
+/// Quoting style of a literal in I18nString format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Quote {
+    /// `'...'`, escaping `'`.
+    Single,
+    /// `"..."`, escaping `"`.
+    Double,
+    /// `r#'...'#` with enough `#` to not end early, without escapes.
+    Raw { hashes: usize },
+}
+
+impl Quote {
+    /// Choose the quoting with the fewest escapes, preferring single quotes, then double quotes on ties.
+    ///
+    /// Raw literals are not used for text with control characters, which are always escaped.
+    pub(crate) fn choose(text: &str) -> Self {
+        let (mut single, mut double, mut backslashes, mut control) = (0, 0, 0, false);
+        for c in text.chars() {
+            match c {
+                '\'' => single += 1,
+                '"' => double += 1,
+                '\\' => backslashes += 1,
+                c => control |= c.is_control(),
+            }
+        }
+
+        let raw = if control { usize::MAX } else { 0 };
+        if single + backslashes <= (double + backslashes).min(raw) {
+            Quote::Single
+        } else if double + backslashes <= raw {
+            Quote::Double
+        } else {
+            // a raw literal ends at the first quote followed by as many hashes as it started with
+            let hashes = text
+                .match_indices('\'')
+                .map(|(n, _)| text[n + 1..].len() - text[n + 1..].trim_start_matches('#').len() + 1)
+                .max()
+                .unwrap_or(0);
+            Quote::Raw { hashes }
+        }
+    }
+
+    /// Write a text quoted in this style.
+    pub(crate) fn write<W: core::fmt::Write>(self, output: &mut W, text: &str) -> core::fmt::Result {
+        match self {
+            Quote::Single | Quote::Double => {
+                let quote = if self == Quote::Single { '\'' } else { '"' };
+                output.write_char(quote)?;
+                for c in text.chars() {
+                    write_escaped_char_in(output, c, quote)?;
+                }
+                output.write_char(quote)
+            }
+            Quote::Raw { hashes } => {
+                output.write_char('r')?;
+                for _ in 0..hashes {
+                    output.write_char('#')?;
+                }
+                output.write_char('\'')?;
+                output.write_str(text)?;
+                output.write_char('\'')?;
+                for _ in 0..hashes {
+                    output.write_char('#')?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Write a text as a literal in I18nString format, quoted with the fewest escapes.
+pub(crate) fn write_quoted<W: core::fmt::Write>(output: &mut W, text: &str) -> core::fmt::Result {
+    Quote::choose(text).write(output, text)
+}
+
+/// Quote a text as a literal in I18nString format with the fewest escapes.
+///
+/// Text without apostrophes and backslashes is single quoted like before, otherwise double quotes or a raw
+/// literal `r#'...'#` are used if they need fewer escapes.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::escape::quote;
+///
+/// assert_eq!(quote("Bonjour"), "'Bonjour'");
+/// assert_eq!(quote("l'utilisateur"), "\"l'utilisateur\"");
+/// assert_eq!(quote("C:\\dir"), "r'C:\\dir'");
+/// assert_eq!(quote("l'\"utilisateur\"'#"), "r##'l'\"utilisateur\"'#'##");
+/// assert_eq!(quote("it's\n"), "\"it's\\n\"");
+/// ```
+pub fn quote(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() + 2);
+    let _ = write_quoted(&mut output, text);
+    output
+}
+
+/// Get the length of a control character at the start of UTF-8 bytes, 1 for C0 controls and `DEL`, 2 for C1 controls.
+fn control_len(input: &[u8]) -> Option<usize> {
+    match input {
+        [c, ..] if *c < 0x20 || *c == 0x7f => Some(1),
+        [0xc2, 0x80..=0x9f, ..] => Some(2),
+        _ => None,
+    }
+}
+
 /// Escape special characters in a byte slice to fit I18nString format.
+///
+/// Bytes are treated as UTF-8, escaping the same characters as [`escape`] does.
 pub fn escape_bytes(input: &[u8]) -> Cow<'_, [u8]> {
-    if !input.iter().any(|&c| c == b'\'' || c == b'\\' || c == b'\n' || c == b'\t') {
+    if !(0..input.len()).any(|n| matches!(input[n], b'\'' | b'\\') || control_len(&input[n..]).is_some()) {
         return Cow::Borrowed(input);
     }
 
-    let mut output = Vec::with_capacity(input.len());
-    for &c in input {
-        match c {
-            b'\'' => output.extend_from_slice(b"\\'"),
-            b'\\' => output.extend_from_slice(b"\\\\"),
-            b'\n' => output.extend_from_slice(b"\\n"),
-            b'\t' => output.extend_from_slice(b"\\t"),
-            _ => output.push(c),
+    let mut output = Vec::with_capacity(input.len() + 8);
+    let mut n = 0;
+    while n < input.len() {
+        let c = input[n];
+        match control_len(&input[n..]) {
+            Some(len) => {
+                let c = if len == 1 { c as char } else { input[n + 1] as char };
+                let mut escaped = String::new();
+                let _ = write_escaped_char(&mut escaped, c);
+                output.extend_from_slice(escaped.as_bytes());
+                n += len;
+            }
+            None => {
+                match c {
+                    b'\'' => output.extend_from_slice(b"\\'"),
+                    b'\\' => output.extend_from_slice(b"\\\\"),
+                    _ => output.push(c),
+                }
+                n += 1;
+            }
         }
     }
     Cow::Owned(output)
 }
 
-/// A wrapper for a formatter that escapes special characters in I18nString format.
-pub struct Escaped<W>(pub W);
+/// Decode the escape sequence following a backslash at the start of `rest`, returns the character and the length.
+///
+/// Unknown escapes and malformed `\u{...}` sequences are `None` if `strict` is set, otherwise the escaped
+/// character stands for itself.
+pub(crate) fn unescape_sequence(rest: &str, strict: bool) -> Option<(char, usize)> {
+    let c = rest.chars().next()?;
+    let unescaped = match c {
+        '\'' | '"' | '\\' => c,
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        '0' => '\0',
+        'u' => {
+            let code = rest[1..]
+                .strip_prefix('{')
+                .and_then(|hex| hex.split_once('}'))
+                .filter(|(hex, _)| (1..=6).contains(&hex.len()) && hex.bytes().all(|c| c.is_ascii_hexdigit()))
+                .and_then(|(hex, _)| Some((char::from_u32(u32::from_str_radix(hex, 16).ok()?)?, hex.len() + 3)));
+            match code {
+                Some(code) => return Some(code),
+                None if strict => return None,
+                None => c,
+            }
+        }
+        _ if strict => return None,
+        c => c,
+    };
+    Some((unescaped, c.len_utf8()))
+}
+
+/// Unescape a string escaped by [`escape`], rejecting unknown escape sequences.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::escape::{escape, unescape};
+///
+/// let text = "line\r\n\0\u{7f}'";
+/// assert_eq!(unescape(&escape(text)).unwrap(), text);
+/// assert_eq!(unescape("caf\\u{e9}").unwrap(), "café");
+/// assert!(unescape("\\q").is_err());
+/// assert!(unescape("\\u{d800}").is_err());
+/// ```
+pub fn unescape(input: &str) -> Result<Cow<'_, str>, InvalidFormat> {
+    if !input.contains('\\') {
+        return Ok(Cow::Borrowed(input));
+    }
+
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(n) = rest.find('\\') {
+        output.push_str(&rest[..n]);
+        let (c, len) = unescape_sequence(&rest[n + 1..], true).ok_or(InvalidFormat)?;
+        output.push(c);
+        rest = &rest[n + 1 + len..];
+    }
+    output.push_str(rest);
+    Ok(Cow::Owned(output))
+}
+
+/// A wrapper for a formatter or writer that escapes special characters in I18nString format.
+///
+/// The writer is private since `io::Write` keeps state between writes, create the wrapper with
+/// [`new`](Self::new) instead of `Escaped(writer)` and access the writer with [`get_ref`](Self::get_ref),
+/// [`get_mut`](Self::get_mut) and [`into_inner`](Self::into_inner) instead of `.0`.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use core::fmt::Write;
+///
+/// use i18n_string::escape::Escaped;
+///
+/// let mut escaped = Escaped::new(String::new());
+/// escaped.write_str("it's\n").unwrap();
+/// assert_eq!(escaped.get_ref(), "it\\'s\\n");
+/// assert_eq!(escaped.into_inner(), "it\\'s\\n");
+/// ```
+pub struct Escaped<W> {
+    inner: W,
+    /// Whether a lead byte of a possible C1 control character was consumed but not written yet.
+    #[cfg(feature = "std")]
+    lead: bool,
+}
 
 impl<W> Escaped<W> {
     /// Create a new `Escaped` formatter.
     pub fn new(write: W) -> Self {
-        Self(write)
+        Self {
+            inner: write,
+            #[cfg(feature = "std")]
+            lead: false,
+        }
+    }
+
+    /// Get a reference to the inner formatter.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner formatter.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
     }
 
     /// Get the inner formatter.
+    ///
+    /// A pending incomplete UTF-8 sequence of `io::Write` is discarded, call `flush` before to write it.
     pub fn into_inner(self) -> W {
-        self.0
+        self.inner
     }
 }
 
 impl<W: core::fmt::Write> core::fmt::Write for Escaped<W> {
     fn write_str(&mut self, s: &str) -> core::fmt::Result {
-        self.0.write_str(escape(s).as_ref())
+        self.inner.write_str(escape(s).as_ref())
     }
 
     fn write_char(&mut self, c: char) -> core::fmt::Result {
-        match c {
-            '\'' => self.0.write_str("\\'"),
-            '\\' => self.0.write_str("\\\\"),
-            '\n' => self.0.write_str("\\n"),
-            '\t' => self.0.write_str("\\t"),
-            _ => self.0.write_char(c),
-        }
+        write_escaped_char(&mut self.inner, c)
     }
 }
 
 #[cfg(feature = "std")]
 impl<W: std::io::Write> std::io::Write for Escaped<W> {
+    /// Escape the bytes and write them all to the inner writer, returns the number of input bytes consumed.
+    ///
+    /// Escape sequences are never split, a trailing lead byte of a C1 control character is kept until the next
+    /// write decides whether it is escaped.
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.0.write(escape_bytes(buf).as_ref())
+        let Some((&last, _)) = buf.split_last() else {
+            return Ok(0);
+        };
+
+        let mut sequence = compact_str::CompactString::default();
+        let mut start = 0;
+        let mut n = 0;
+        if core::mem::take(&mut self.lead) {
+            if let 0x80..=0x9f = buf[0] {
+                let _ = write_escaped_char(&mut sequence, buf[0] as char);
+                self.inner.write_all(sequence.as_bytes())?;
+                start = 1;
+                n = 1;
+            } else {
+                self.inner.write_all(&[0xc2])?;
+            }
+        }
+
+        let end = if last == 0xc2 && n < buf.len() {
+            buf.len() - 1
+        } else {
+            buf.len()
+        };
+        while n < end {
+            let escaped = match control_len(&buf[n..end]) {
+                Some(1) => Some((buf[n] as char, 1)),
+                Some(_) => Some((buf[n + 1] as char, 2)),
+                None if matches!(buf[n], b'\'' | b'\\') => Some((buf[n] as char, 1)),
+                None => None,
+            };
+            match escaped {
+                Some((c, len)) => {
+                    self.inner.write_all(&buf[start..n])?;
+                    sequence.clear();
+                    let _ = write_escaped_char(&mut sequence, c);
+                    self.inner.write_all(sequence.as_bytes())?;
+                    n += len;
+                    start = n;
+                }
+                None => n += 1,
+            }
+        }
+        self.inner.write_all(&buf[start..end])?;
+        self.lead = end < buf.len();
+
+        Ok(buf.len())
     }
 
+    /// Write a pending lead byte unescaped and flush the inner writer.
     fn flush(&mut self) -> std::io::Result<()> {
-        self.0.flush()
+        if core::mem::take(&mut self.lead) {
+            self.inner.write_all(&[0xc2])?;
+        }
+        self.inner.flush()
     }
 }
+
+/// A reader unescaping I18nString format escapes of an inner reader, the inverse of `Escaped` as `io::Write`.
+///
+/// Escape sequences split between reads of the inner reader are buffered, so large literals can be decoded
+/// in chunks. Unknown or truncated escape sequences fail with `InvalidData`.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use std::io::{Read, Write};
+///
+/// use i18n_string::escape::{Escaped, Unescaped};
+///
+/// let mut escaped = Escaped::new(Vec::new());
+/// escaped.write_all("it's a\r\nb\x1b".as_bytes()).unwrap();
+/// let escaped = escaped.into_inner();
+/// assert_eq!(escaped, b"it\\'s a\\r\\nb\\u{1b}");
+///
+/// let mut text = String::new();
+/// Unescaped::new(escaped.as_slice()).read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "it's a\r\nb\x1b");
+/// ```
+#[cfg(feature = "std")]
+pub struct Unescaped<R> {
+    inner: R,
+    /// Input not yet decoded, starting with an incomplete escape sequence.
+    input: Vec<u8>,
+    /// Decoded output not yet read.
+    output: Vec<u8>,
+    position: usize,
+    eof: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R> Unescaped<R> {
+    /// Create a new `Unescaped` reader.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            input: Vec::new(),
+            output: Vec::new(),
+            position: 0,
+            eof: false,
+        }
+    }
+
+    /// Get the inner reader, buffered input is discarded.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Decode the buffered input up to the first incomplete escape sequence.
+    fn decode(&mut self) -> std::io::Result<()> {
+        let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, InvalidFormat);
+
+        let mut n = 0;
+        while n < self.input.len() {
+            let Some(backslash) = self.input[n..].iter().position(|&c| c == b'\\') else {
+                self.output.extend_from_slice(&self.input[n..]);
+                n = self.input.len();
+                break;
+            };
+            self.output.extend_from_slice(&self.input[n..n + backslash]);
+            n += backslash;
+
+            // an escape is a backslash and an ASCII character, or `\u{` with up to 6 digits and `}`
+            let rest = &self.input[n + 1..];
+            let len = match rest.first() {
+                None => 0,
+                Some(b'u') => rest.iter().take(10).position(|&c| c == b'}').map_or(0, |end| end + 1),
+                Some(c) if c.is_ascii() => 1,
+                Some(_) => return Err(invalid()),
+            };
+            if len == 0 {
+                if self.eof || rest.len() >= 10 {
+                    return Err(invalid());
+                }
+                break;
+            }
+
+            let sequence = core::str::from_utf8(&rest[..len]).map_err(|_| invalid())?;
+            let (c, _) = unescape_sequence(sequence, true)
+                .filter(|(_, used)| *used == len)
+                .ok_or_else(invalid)?;
+            self.output.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            n += 1 + len;
+        }
+        self.input.drain(..n);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> std::io::Read for Unescaped<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.output.len() {
+            if self.eof {
+                return Ok(0);
+            }
+            self.output.clear();
+            self.position = 0;
+
+            let mut chunk = [0; 4096];
+            let read = self.inner.read(&mut chunk)?;
+            self.eof = read == 0;
+            self.input.extend_from_slice(&chunk[..read]);
+            self.decode()?;
+        }
+
+        let len = buf.len().min(self.output.len() - self.position);
+        buf[..len].copy_from_slice(&self.output[self.position..self.position + len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+/// Target context for escaping text embedded into other formats.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum Context {
+    /// HTML text or attribute value.
+    Html,
+    /// CommonMark / GitHub flavored Markdown.
+    Markdown,
+    /// Terminal output, strips ANSI escape sequences and control characters.
+    Terminal,
+}
+
+impl Context {
+    /// Escape a string for this context.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::escape::Context;
+    ///
+    /// assert_eq!(Context::Html.escape("<b>&</b>"), "&lt;b&gt;&amp;&lt;/b&gt;");
+    /// assert_eq!(Context::Markdown.escape("**bold**"), "\\*\\*bold\\*\\*");
+    /// assert_eq!(Context::Terminal.escape("\x1b[31mred\x1b[0m"), "red");
+    /// ```
+    pub fn escape(self, input: &str) -> Cow<'_, str> {
+        match self {
+            Context::Html => escape_html(input),
+            Context::Markdown => escape_markdown(input),
+            Context::Terminal => strip_ansi(input),
+        }
+    }
+}
+
+/// Escape HTML special characters in a string.
+pub fn escape_html(input: &str) -> Cow<'_, str> {
+    if !input.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut output = String::with_capacity(input.len() + 16);
+    for c in input.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            _ => output.push(c),
+        }
+    }
+    Cow::Owned(output)
+}
+
+/// Escape Markdown special characters in a string with backslashes.
+pub fn escape_markdown(input: &str) -> Cow<'_, str> {
+    const SPECIAL: [char; 20] = [
+        '\\', '`', '*', '_', '{', '}', '[', ']', '(', ')', '#', '+', '-', '.', '!', '|', '<', '>', '~', '&',
+    ];
+
+    if !input.contains(SPECIAL) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut output = String::with_capacity(input.len() + 16);
+    for c in input.chars() {
+        if SPECIAL.contains(&c) {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+    Cow::Owned(output)
+}
+
+/// Strip ANSI escape sequences and control characters (except `\n` and `\t`) from a string.
+pub fn strip_ansi(input: &str) -> Cow<'_, str> {
+    fn is_stripped(c: char) -> bool {
+        c.is_control() && c != '\n' && c != '\t'
+    }
+
+    if !input.contains(is_stripped) {
+        return Cow::Borrowed(input);
+    }
+
+    enum State {
+        Normal,
+        Escape,
+        Csi,
+        String { escaping: bool },
+    }
+
+    let mut output = String::with_capacity(input.len());
+    let mut state = State::Normal;
+    for c in input.chars() {
+        state = match state {
+            State::Normal => match c {
+                '\x1b' => State::Escape,
+                '\u{9b}' => State::Csi,
+                '\u{90}' | '\u{9d}' | '\u{9e}' | '\u{9f}' => State::String { escaping: false },
+                _ => {
+                    if !is_stripped(c) {
+                        output.push(c);
+                    }
+                    State::Normal
+                }
+            },
+            State::Escape => match c {
+                '[' => State::Csi,
+                ']' | 'P' | 'X' | '^' | '_' => State::String { escaping: false },
+                // intermediate bytes of a nF sequence
+                '\x20'..='\x2f' => State::Escape,
+                _ => State::Normal,
+            },
+            State::Csi => match c {
+                '\x40'..='\x7e' => State::Normal,
+                _ => State::Csi,
+            },
+            State::String { escaping } => match c {
+                '\x07' | '\u{9c}' => State::Normal,
+                '\\' if escaping => State::Normal,
+                _ => State::String { escaping: c == '\x1b' },
+            },
+        };
+    }
+    Cow::Owned(output)
+}
@@ -1,23 +1,186 @@
-use core::fmt::{Formatter, Write};
+use alloc::vec::Vec;
+use core::fmt::Write;
 
-use crate::{I18nString, escape::Escaped};
+use crate::{
+    I18nString,
+    escape::{self, Escaped},
+    pretty::Layout,
+    redact::{self, Redaction},
+    value::Value,
+};
 
-pub fn format_to(f: &mut Formatter<'_>, s: &I18nString) -> core::fmt::Result {
-    match s {
-        I18nString::Literal(s) => {
-            f.write_str("'")?;
-            Escaped::new(&mut *f).write_str(s)?;
-            f.write_str("'")
+fn format_value_to<W: Write>(f: &mut W, value: &Value) -> core::fmt::Result {
+    match value {
+        Value::Integer(v) => write!(f, "int!({v})"),
+        Value::Decimal(d) => write!(f, "dec!({d})"),
+        Value::Percent(d) => write!(f, "pct!({d})"),
+        Value::Currency(d, code) => {
+            write!(f, "cur!({d},")?;
+            escape::write_quoted(f, code)?;
+            f.write_str(")")
         }
-        I18nString::Template(template, args) => {
-            f.write_str("t!('")?;
-            Escaped::new(&mut *f).write_str(template)?;
-            f.write_str("'")?;
-            for arg in args {
-                f.write_str(",")?;
-                format_to(f, arg)?;
+        Value::Date(v) => write!(f, "date!({v})"),
+        Value::DateTime(v) => write!(f, "dt!({v})"),
+        Value::Duration(v) => write!(f, "dur!({v})"),
+    }
+}
+
+/// Format a string with an explicit stack of argument iterators instead of recursion.
+///
+/// Sensitive arguments are replaced by their redaction if `redaction` is given.
+pub fn format_to<W: Write>(f: &mut W, s: &I18nString, redaction: Option<Redaction>) -> core::fmt::Result {
+    // argument iterators and the separator written before each argument
+    let mut stack: Vec<(core::slice::Iter<'_, I18nString>, &str)> = Vec::new();
+    let mut next = Some(s);
+
+    loop {
+        match next.take() {
+            Some(I18nString::Literal(s)) => {
+                escape::write_quoted(f, s)?;
+            }
+            Some(I18nString::Template(template, args)) => {
+                f.write_str("t!(")?;
+                if let Some(id) = template.id() {
+                    f.write_str("id=")?;
+                    escape::write_quoted(f, id)?;
+                    f.write_str(",")?;
+                }
+                if let Some(context) = template.context() {
+                    f.write_str("ctx=")?;
+                    escape::write_quoted(f, context)?;
+                    f.write_str(",")?;
+                }
+                if let Some(note) = template.note() {
+                    f.write_str("note=")?;
+                    escape::write_quoted(f, note)?;
+                    f.write_str(",")?;
+                }
+                for (index, note) in template.placeholder_notes() {
+                    write!(f, "note{index}=")?;
+                    escape::write_quoted(f, note)?;
+                    f.write_str(",")?;
+                }
+                escape::write_quoted(f, template)?;
+                stack.push((args.iter(), ","));
+            }
+            Some(I18nString::Value(value)) => format_value_to(f, value)?,
+            Some(I18nString::Sensitive(inner)) => {
+                f.write_str("s!(")?;
+                match redaction {
+                    Some(redaction) => {
+                        f.write_str("'")?;
+                        redact::write_redacted(&mut Escaped::new(&mut *f), inner, redaction)?;
+                        f.write_str("')")?;
+                    }
+                    None => stack.push((core::slice::from_ref(&**inner).iter(), "")),
+                }
+            }
+            None => {}
+        }
+
+        let Some((args, separator)) = stack.last_mut() else {
+            return Ok(());
+        };
+        match args.next() {
+            Some(arg) => {
+                f.write_str(separator)?;
+                next = Some(arg);
+            }
+            None => {
+                stack.pop();
+                f.write_str(")")?;
+            }
+        }
+    }
+}
+
+/// Writer counting characters, failing once more than `budget` are written.
+struct Fits {
+    budget: usize,
+}
+
+impl Write for Fits {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let len = s.chars().count();
+        self.budget = self.budget.checked_sub(len).ok_or(core::fmt::Error)?;
+        Ok(())
+    }
+}
+
+/// Check whether the compact form of a string fits in `budget` characters, formatting at most `budget + 1` of them.
+fn fits(s: &I18nString, redaction: Option<Redaction>, budget: usize) -> bool {
+    format_to(&mut Fits { budget }, s, redaction).is_ok()
+}
+
+fn write_indent<W: Write>(f: &mut W, width: usize) -> core::fmt::Result {
+    for _ in 0..width {
+        f.write_char(' ')?;
+    }
+    Ok(())
+}
+
+/// Format a string over multiple lines, breaking the templates and sensitive arguments that do not fit in the width.
+///
+/// Parts that fit in the remaining width are written compact, on one line.
+pub fn format_pretty_to<W: Write>(f: &mut W, s: &I18nString, redaction: Option<Redaction>, layout: &Layout) -> core::fmt::Result {
+    // argument iterators, the indentation of their lines, and whether no argument was written yet
+    let mut stack: Vec<(core::slice::Iter<'_, I18nString>, usize, bool)> = Vec::new();
+    let mut next = Some(s);
+
+    loop {
+        if let Some(s) = next.take() {
+            let column = stack.last().map_or(0, |(_, indent, _)| *indent);
+            let inner = column + layout.indent;
+            match s {
+                I18nString::Template(template, args) if !fits(s, redaction, layout.width.saturating_sub(column)) => {
+                    f.write_str("t!(")?;
+                    let params = [("id", template.id()), ("ctx", template.context()), ("note", template.note())];
+                    for (name, value) in params.into_iter().filter_map(|(name, value)| Some((name, value?))) {
+                        f.write_char('\n')?;
+                        write_indent(f, inner)?;
+                        write!(f, "{name}=")?;
+                        escape::write_quoted(f, value)?;
+                        f.write_str(",")?;
+                    }
+                    for (index, note) in template.placeholder_notes() {
+                        f.write_char('\n')?;
+                        write_indent(f, inner)?;
+                        write!(f, "note{index}=")?;
+                        escape::write_quoted(f, note)?;
+                        f.write_str(",")?;
+                    }
+                    f.write_char('\n')?;
+                    write_indent(f, inner)?;
+                    escape::write_quoted(f, template)?;
+                    stack.push((args.iter(), inner, false));
+                }
+                I18nString::Sensitive(inner_s)
+                    if redaction.is_none() && !fits(s, redaction, layout.width.saturating_sub(column)) =>
+                {
+                    f.write_str("s!(")?;
+                    stack.push((core::slice::from_ref(&**inner_s).iter(), inner, true));
+                }
+                s => format_to(f, s, redaction)?,
+            }
+        }
+
+        let Some((args, indent, first)) = stack.last_mut() else {
+            return Ok(());
+        };
+        match args.next() {
+            Some(arg) => {
+                f.write_str(if *first { "\n" } else { ",\n" })?;
+                write_indent(f, *indent)?;
+                *first = false;
+                next = Some(arg);
+            }
+            None => {
+                let indent = *indent - layout.indent;
+                stack.pop();
+                f.write_char('\n')?;
+                write_indent(f, indent)?;
+                f.write_str(")")?;
             }
-            f.write_str(")")
         }
     }
 }
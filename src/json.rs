@@ -0,0 +1,189 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::InvalidFormat;
+
+/// Maximum nesting of arrays and objects accepted by [`parse`].
+const MAX_DEPTH: usize = 64;
+
+/// A parsed JSON value, numbers are kept as their source text.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+/// Write a JSON string literal.
+pub(crate) fn write_string<W: Write>(output: &mut W, s: &str) -> core::fmt::Result {
+    output.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => output.write_str("\\\"")?,
+            '\\' => output.write_str("\\\\")?,
+            '\n' => output.write_str("\\n")?,
+            '\r' => output.write_str("\\r")?,
+            '\t' => output.write_str("\\t")?,
+            c if (c as u32) < 0x20 || c == '\x7f' => write!(output, "\\u{:04x}", c as u32)?,
+            c => output.write_char(c)?,
+        }
+    }
+    output.write_char('"')
+}
+
+/// Write an optional JSON string literal, `null` if absent.
+pub(crate) fn write_optional_string<W: Write>(output: &mut W, s: Option<&str>) -> core::fmt::Result {
+    match s {
+        Some(s) => write_string(output, s),
+        None => output.write_str("null"),
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        self.skip_whitespace();
+        if self.input[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> Result<(), InvalidFormat> {
+        if self.eat(s) { Ok(()) } else { Err(InvalidFormat) }
+    }
+
+    fn hex4(&mut self) -> Result<u32, InvalidFormat> {
+        let hex = self.input.get(self.pos..self.pos + 4).ok_or(InvalidFormat)?;
+        self.pos += 4;
+        if !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(InvalidFormat);
+        }
+        u32::from_str_radix(hex, 16).map_err(|_| InvalidFormat)
+    }
+
+    fn string(&mut self) -> Result<String, InvalidFormat> {
+        self.expect("\"")?;
+        let mut output = String::new();
+        loop {
+            let c = self.input[self.pos..].chars().next().ok_or(InvalidFormat)?;
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(output),
+                '\\' => {
+                    let c = self.input[self.pos..].chars().next().ok_or(InvalidFormat)?;
+                    self.pos += c.len_utf8();
+                    match c {
+                        '"' | '\\' | '/' => output.push(c),
+                        'b' => output.push('\x08'),
+                        'f' => output.push('\x0c'),
+                        'n' => output.push('\n'),
+                        'r' => output.push('\r'),
+                        't' => output.push('\t'),
+                        'u' => {
+                            let high = self.hex4()?;
+                            let code = if (0xd800..0xdc00).contains(&high) {
+                                if !self.input[self.pos..].starts_with("\\u") {
+                                    return Err(InvalidFormat);
+                                }
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(InvalidFormat);
+                                }
+                                0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                            } else {
+                                high
+                            };
+                            output.push(char::from_u32(code).ok_or(InvalidFormat)?);
+                        }
+                        _ => return Err(InvalidFormat),
+                    }
+                }
+                c if (c as u32) < 0x20 => return Err(InvalidFormat),
+                c => output.push(c),
+            }
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, InvalidFormat> {
+        if depth > MAX_DEPTH {
+            return Err(InvalidFormat);
+        }
+
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        match rest.chars().next().ok_or(InvalidFormat)? {
+            '"' => self.string().map(Value::String),
+            '[' => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                if !self.eat("]") {
+                    loop {
+                        values.push(self.value(depth + 1)?);
+                        if self.eat("]") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                Ok(Value::Array(values))
+            }
+            '{' => {
+                self.pos += 1;
+                let mut members = Vec::new();
+                if !self.eat("}") {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.expect(":")?;
+                        members.push((key, self.value(depth + 1)?));
+                        if self.eat("}") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                Ok(Value::Object(members))
+            }
+            _ if self.eat("null") => Ok(Value::Null),
+            _ if self.eat("true") => Ok(Value::Bool(true)),
+            _ if self.eat("false") => Ok(Value::Bool(false)),
+            _ => {
+                let len = rest
+                    .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+                    .unwrap_or(rest.len());
+                if len == 0 || rest[..len].parse::<f64>().is_err() {
+                    return Err(InvalidFormat);
+                }
+                self.pos += len;
+                Ok(Value::Number(rest[..len].into()))
+            }
+        }
+    }
+}
+
+/// Parse a JSON document.
+pub(crate) fn parse(input: &str) -> Result<Value, InvalidFormat> {
+    let mut parser = Parser { input, pos: 0 };
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.pos != input.len() {
+        return Err(InvalidFormat);
+    }
+    Ok(value)
+}
@@ -4,12 +4,34 @@
 extern crate alloc;
 extern crate core;
 
+pub mod bidi;
+#[cfg(feature = "std")]
+pub mod cache;
+pub mod catalog;
 pub mod escape;
 mod format;
+mod json;
+pub mod limit;
+pub mod lint;
+pub mod locale;
+mod locale_data;
+pub mod number;
 mod parse;
+pub mod pretty;
+#[cfg(feature = "std")]
+pub mod record;
+pub mod redact;
+#[cfg(feature = "std")]
+pub mod registry;
+pub mod stream;
+mod template;
 #[cfg(test)]
 mod tests;
 mod translate;
+pub mod value;
+pub mod visit;
+#[cfg(feature = "std")]
+pub mod watch;
 
 use alloc::{
     borrow::Cow,
@@ -18,6 +40,7 @@ use alloc::{
     rc::Rc,
     string::{String, ToString},
     sync::Arc,
+    vec::Vec,
 };
 use core::{
     fmt::{Debug, Display, Formatter},
@@ -26,8 +49,18 @@ use core::{
 
 use compact_str::CompactString;
 
+use crate::{
+    bidi::Direction,
+    escape::Context,
+    limit::{LimitExceeded, Limits},
+    locale::Locale,
+    pretty::{Layout, Pretty},
+    redact::{Redacted, Redaction},
+    value::Value,
+};
+
 /// Error type for invalid I18nString format.
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct InvalidFormat;
 
 impl Display for InvalidFormat {
@@ -39,18 +72,90 @@ impl Display for InvalidFormat {
 #[cfg(feature = "std")]
 impl std::error::Error for InvalidFormat {}
 
+/// Error type for parsing an I18nString with limits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The input is not a valid I18nString.
+    InvalidFormat,
+    /// The input exceeded a limit.
+    LimitExceeded(LimitExceeded),
+}
+
+impl From<InvalidFormat> for ParseError {
+    fn from(_: InvalidFormat) -> Self {
+        ParseError::InvalidFormat
+    }
+}
+
+impl From<LimitExceeded> for ParseError {
+    fn from(e: LimitExceeded) -> Self {
+        ParseError::LimitExceeded(e)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseError::InvalidFormat => Display::fmt(&InvalidFormat, f),
+            ParseError::LimitExceeded(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
 /// Trait for resolving translated I18nString templates.
 pub trait Resolver {
     /// Resolve a template string.
     fn resolve<'s>(&'s self, template: &'s str) -> Cow<'s, str>;
+
+    /// Look up the translation of a template string, `None` if there is no translation.
+    ///
+    /// The default implementation treats `resolve` returning the template itself as a missing translation.
+    fn lookup<'s>(&'s self, template: &'s str) -> Option<Cow<'s, str>> {
+        match self.resolve(template) {
+            Cow::Borrowed(resolved) if core::ptr::eq(resolved, template) => None,
+            resolved => Some(resolved),
+        }
+    }
+
+    /// Resolve a template string with a disambiguating context, like `msgctxt` of gettext.
+    ///
+    /// The default implementation ignores the context.
+    fn resolve_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Cow<'s, str> {
+        let _ = context;
+        self.resolve(template)
+    }
+
+    /// Look up the translation of a template string with a disambiguating context, `None` if there is no translation.
+    ///
+    /// The default implementation ignores the context.
+    fn lookup_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Option<Cow<'s, str>> {
+        let _ = context;
+        self.lookup(template)
+    }
 }
 
 macro_rules! impl_resolver_delegate {
     ($typ:ty) => {
-        impl<T: Resolver> Resolver for $typ {
+        impl<T: Resolver + ?Sized> Resolver for $typ {
             fn resolve<'s>(&'s self, template: &'s str) -> Cow<'s, str> {
                 Resolver::resolve(&**self, template)
             }
+
+            fn lookup<'s>(&'s self, template: &'s str) -> Option<Cow<'s, str>> {
+                Resolver::lookup(&**self, template)
+            }
+
+            fn resolve_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Cow<'s, str> {
+                Resolver::resolve_in_context(&**self, context, template)
+            }
+
+            fn lookup_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Option<Cow<'s, str>> {
+                Resolver::lookup_in_context(&**self, context, template)
+            }
         }
     };
 }
@@ -69,6 +174,314 @@ impl Resolver for NoResolver {
     fn resolve<'s>(&'s self, template: &'s str) -> Cow<'s, str> {
         template.into()
     }
+
+    fn lookup<'s>(&'s self, _template: &'s str) -> Option<Cow<'s, str>> {
+        None
+    }
+
+    fn lookup_in_context<'s>(&'s self, _context: Option<&'s str>, _template: &'s str) -> Option<Cow<'s, str>> {
+        None
+    }
+}
+
+/// Options for translating an `I18nString`.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{TranslateOptions, bidi::Direction};
+///
+/// let options = TranslateOptions::new().isolate(Direction::from_language("ar"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TranslateOptions {
+    locale: Option<Locale>,
+    isolation: Option<Direction>,
+    literal_escape: Option<Context>,
+    limits: Limits,
+    redaction: Option<Redaction>,
+}
+
+impl TranslateOptions {
+    /// Create a new `TranslateOptions` with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Format typed values according to the given locale.
+    ///
+    /// Values are formatted with the most specific bundled locale data, e.g. `de-AT` falls back to `de`,
+    /// and unknown locales use neutral root formatting.
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    /// Wrap substituted arguments in Unicode bidi isolates for a target text of the given direction.
+    ///
+    /// Arguments with the same direction as the target are left as is, arguments with the opposite
+    /// direction are wrapped in `LRI`/`RLI` ... `PDI`, and arguments without any strong character are
+    /// wrapped in `FSI` ... `PDI`.
+    pub fn isolate(mut self, target: Direction) -> Self {
+        self.isolation = Some(target);
+        self
+    }
+
+    /// Escape literal strings for the given context, template text is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{I18nString, NoResolver, TranslateOptions, escape::Context};
+    ///
+    /// let s = I18nString::template("<b>{0}</b> joined", [I18nString::literal("<script>")]);
+    /// let options = TranslateOptions::new().escape_literals(Context::Html);
+    /// assert_eq!(s.translate_with(NoResolver, &options), "<b>&lt;script&gt;</b> joined");
+    /// ```
+    pub fn escape_literals(mut self, context: Context) -> Self {
+        self.literal_escape = Some(context);
+        self
+    }
+
+    /// Limit the translation, e.g. when resolving templates from untrusted catalogs.
+    ///
+    /// Without limits translation never fails. With limits, `translate_with` stops at the exceeded limit and
+    /// returns the output so far, use `try_translate_with` to detect it.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Replace sensitive arguments instead of translating them, e.g. for logging.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{I18nString, NoResolver, TranslateOptions, redact::Redaction};
+    ///
+    /// let s = I18nString::template("mail sent to {0}", [I18nString::sensitive(I18nString::literal("bob@example.com"))]);
+    /// assert_eq!(s.translate(NoResolver), "mail sent to bob@example.com");
+    /// let options = TranslateOptions::new().redact(Redaction::Mask);
+    /// assert_eq!(s.translate_with(NoResolver, &options), "mail sent to [redacted]");
+    /// ```
+    pub fn redact(mut self, redaction: Redaction) -> Self {
+        self.redaction = Some(redaction);
+        self
+    }
+}
+
+/// The key of a template, its source text with an optional disambiguating context and stable id.
+///
+/// Dereferences to the source text, so it can be used like the template string itself.
+///
+/// Translator notes only describe a template, they are ignored when comparing, ordering and hashing keys.
+/// Strings differing only in notes are equal and share a cached template.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{I18nString, TemplateKey};
+///
+/// let s = I18nString::template(TemplateKey::new("Open").with_context("verb"), []);
+/// assert_eq!(s.to_string(), "t!(ctx='verb','Open')");
+/// assert_eq!(TemplateKey::new("Open").with_note("A button"), TemplateKey::new("Open"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TemplateKey {
+    text: CompactString,
+    context: Option<CompactString>,
+    id: Option<CompactString>,
+    note: Option<CompactString>,
+    placeholder_notes: Vec<(usize, CompactString)>,
+}
+
+impl TemplateKey {
+    /// Create a new `TemplateKey` without context.
+    pub fn new<S: Into<CompactString>>(text: S) -> Self {
+        Self {
+            text: text.into(),
+            context: None,
+            id: None,
+            note: None,
+            placeholder_notes: Vec::new(),
+        }
+    }
+
+    /// Set the context, like `msgctxt` of gettext.
+    pub fn with_context<S: Into<CompactString>>(mut self, context: S) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+
+    /// Get the source text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the source text for modification.
+    pub fn text_mut(&mut self) -> &mut CompactString {
+        &mut self.text
+    }
+
+    /// Get the context.
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    /// Set or remove the context.
+    pub fn set_context(&mut self, context: Option<CompactString>) {
+        self.context = context;
+    }
+
+    /// Set a stable id, resolvers look up the id instead of the source text.
+    ///
+    /// The source text becomes the default translation, used when the id has no translation,
+    /// so copy edits of the source text do not invalidate existing translations.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{I18nString, NoResolver, TemplateKey, catalog::{Catalog, Message}};
+    ///
+    /// let s = I18nString::template(TemplateKey::new("Not found: {0}").with_id("err.nf"), [I18nString::literal("a.txt")]);
+    /// assert_eq!(s.to_string(), "t!(id='err.nf','Not found: {0}','a.txt')");
+    /// assert_eq!(s.translate(NoResolver), "Not found: a.txt");
+    ///
+    /// let mut catalog = Catalog::new();
+    /// catalog.insert(Message::new("err.nf", "Introuvable : {0}"));
+    /// assert_eq!(s.translate(&catalog), "Introuvable : a.txt");
+    /// ```
+    pub fn with_id<S: Into<CompactString>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Get the stable id.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Set or remove the stable id.
+    pub fn set_id(&mut self, id: Option<CompactString>) {
+        self.id = id;
+    }
+
+    /// Set a note for translators, it does not affect the translation.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{I18nString, I18nStringTranslateExt, TemplateKey};
+    ///
+    /// let key = TemplateKey::new("{0} deleted").with_note("Shown after deleting a file").with_placeholder_note(0, "file name");
+    /// let s = I18nString::template(key, [I18nString::literal("a.txt")]);
+    /// assert_eq!(s.to_string(), "t!(note='Shown after deleting a file',note0='file name','{0} deleted','a.txt')");
+    /// assert_eq!(s.to_no_translate_string(), "a.txt deleted");
+    /// ```
+    pub fn with_note<S: Into<CompactString>>(mut self, note: S) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    /// Get the note for translators.
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    /// Set or remove the note for translators.
+    pub fn set_note(&mut self, note: Option<CompactString>) {
+        self.note = note;
+    }
+
+    /// Set a note for translators describing the placeholder `{index}`.
+    pub fn with_placeholder_note<S: Into<CompactString>>(mut self, index: usize, note: S) -> Self {
+        self.set_placeholder_note(index, Some(note.into()));
+        self
+    }
+
+    /// Get the note describing the placeholder `{index}`.
+    pub fn placeholder_note(&self, index: usize) -> Option<&str> {
+        self.placeholder_notes
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, note)| note.as_str())
+    }
+
+    /// Iterate the placeholder notes ordered by placeholder index.
+    pub fn placeholder_notes(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.placeholder_notes.iter().map(|(index, note)| (*index, note.as_str()))
+    }
+
+    /// Set or remove the note describing the placeholder `{index}`.
+    pub fn set_placeholder_note(&mut self, index: usize, note: Option<CompactString>) {
+        let position = self.placeholder_notes.binary_search_by_key(&index, |(i, _)| *i);
+        match (position, note) {
+            (Ok(position), Some(note)) => self.placeholder_notes[position].1 = note,
+            (Ok(position), None) => {
+                self.placeholder_notes.remove(position);
+            }
+            (Err(position), Some(note)) => self.placeholder_notes.insert(position, (index, note)),
+            (Err(_), None) => {}
+        }
+    }
+
+    /// Get the key resolvers look up, the id if set, otherwise the source text.
+    pub fn lookup_key(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.text)
+    }
+}
+
+impl TemplateKey {
+    /// Get the fields identifying the template, without the notes.
+    fn identity(&self) -> (&str, Option<&str>, Option<&str>) {
+        (&self.text, self.context.as_deref(), self.id.as_deref())
+    }
+}
+
+impl PartialEq for TemplateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for TemplateKey {}
+
+impl PartialOrd for TemplateKey {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TemplateKey {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.identity().cmp(&other.identity())
+    }
+}
+
+impl core::hash::Hash for TemplateKey {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.identity().hash(state);
+    }
+}
+
+impl core::ops::Deref for TemplateKey {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl<S: Into<CompactString>> From<S> for TemplateKey {
+    fn from(text: S) -> Self {
+        Self::new(text)
+    }
 }
 
 /// A string that can be translated into multiple languages.
@@ -95,13 +508,31 @@ impl Resolver for NoResolver {
 /// let s = I18nString::template("hello {0}, you are {1}", [I18nString::template("world", []), I18nString::literal("123")]);
 /// assert_eq!(s.translate(&SimpleResolver), "hello <translated world>, you are 123");
 /// ```
+///
+/// # Dropping
+///
+/// `I18nString` implements `Drop` to free deep trees without recursion, so fields cannot be moved out of it
+/// by pattern matching. Take them with [`core::mem::take`] or use a [`Fold`](visit::Fold) instead.
+/// ```
+/// use i18n_string::I18nString;
+///
+/// let mut s = I18nString::template("hello {0}", [I18nString::literal("world")]);
+/// if let I18nString::Template(_, args) = &mut s {
+///     let args = core::mem::take(args);
+///     assert_eq!(args[0], I18nString::literal("world"));
+/// }
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 #[non_exhaustive]
 pub enum I18nString {
     /// A literal string.
     Literal(CompactString),
     /// A template string.
-    Template(CompactString, Box<[I18nString]>),
+    Template(TemplateKey, Box<[I18nString]>),
+    /// A typed value.
+    Value(Value),
+    /// A sensitive argument, replaced when redacting.
+    Sensitive(Box<I18nString>),
 }
 
 impl I18nString {
@@ -132,22 +563,152 @@ impl I18nString {
     /// let s = I18nString::template("hello {}", [I18nString::literal("world")]);
     /// assert_eq!(s, I18nString::Template("hello {}".into(), [I18nString::Literal("world".into())].into()));
     /// ```
-    pub fn template<S: Into<CompactString>, ARGS: IntoIterator<Item = I18nString>>(s: S, args: ARGS) -> Self {
+    pub fn template<K: Into<TemplateKey>, ARGS: IntoIterator<Item = I18nString>>(s: K, args: ARGS) -> Self {
         Self::Template(s.into(), args.into_iter().collect())
     }
+
+    /// Create a new `I18nString::Value` from a typed value.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{I18nString, NoResolver, TranslateOptions, value::Value};
+    ///
+    /// let s = I18nString::template("{0} files", [I18nString::value(12345)]);
+    /// assert_eq!(s, I18nString::Template("{0} files".into(), [I18nString::Value(Value::Integer(12345))].into()));
+    /// let options = TranslateOptions::new().locale("fr".parse().unwrap());
+    /// assert_eq!(s.translate_with(NoResolver, &options), "12\u{202f}345 files");
+    /// ```
+    pub fn value<V: Into<Value>>(value: V) -> Self {
+        Self::Value(value.into())
+    }
+
+    /// Create a new `I18nString::Sensitive` marking a string as sensitive, e.g. an email address or a path.
+    ///
+    /// Sensitive strings are translated as usual, unless the translate options or the display
+    /// of [`redacted`](Self::redacted) redact them.
+    pub fn sensitive(s: I18nString) -> Self {
+        Self::Sensitive(Box::new(s))
+    }
+
+    /// Display the string with sensitive arguments redacted.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{I18nString, redact::Redaction};
+    ///
+    /// let s = I18nString::template("cannot open {0}", [I18nString::sensitive(I18nString::literal("/home/bob"))]);
+    /// assert_eq!(s.to_string(), "t!('cannot open {0}',s!('/home/bob'))");
+    /// assert_eq!(s.redacted(Redaction::Mask).to_string(), "t!('cannot open {0}',s!('[redacted]'))");
+    /// ```
+    pub fn redacted(&self, redaction: Redaction) -> Redacted<'_> {
+        Redacted { s: self, redaction }
+    }
+
+    /// Display the string over multiple indented lines with a layout, `{:#}` uses the default layout.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{I18nString, pretty::Layout};
+    ///
+    /// let s = I18nString::template("Hello {0}", [I18nString::template("{0} and {1}", [I18nString::literal("Alice"), I18nString::literal("Bob")])]);
+    /// let pretty = s.pretty(Layout::new().width(40)).to_string();
+    /// assert_eq!(pretty, "t!(\n    'Hello {0}',\n    t!('{0} and {1}','Alice','Bob')\n)");
+    /// assert_eq!(pretty.parse::<I18nString>().unwrap(), s);
+    /// ```
+    pub fn pretty(&self, layout: Layout) -> Pretty<'_> {
+        Pretty {
+            s: self,
+            layout,
+            redaction: None,
+        }
+    }
+
+    /// Parse an `I18nString` from untrusted input with the given limits.
+    ///
+    /// Parsing with `FromStr` is unlimited, like the default [`Limits`].
+    pub fn parse_with_limits(s: &str, limits: &Limits) -> Result<Self, ParseError> {
+        parse::parse(s, limits, false)
+    }
+
+    /// Parse an `I18nString` with the given limits, rejecting unknown escape sequences.
+    ///
+    /// Other parsing maps unknown escapes like `\q` to the escaped character for compatibility with older output.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{I18nString, limit::Limits};
+    ///
+    /// let s = I18nString::parse_strict("'a\\r\\n\\u{1b}'", &Limits::default()).unwrap();
+    /// assert_eq!(s, I18nString::literal("a\r\n\x1b"));
+    /// assert!(I18nString::parse_strict("'\\q'", &Limits::default()).is_err());
+    /// assert_eq!("'\\q'".parse::<I18nString>().unwrap(), I18nString::literal("q"));
+    /// ```
+    pub fn parse_strict(s: &str, limits: &Limits) -> Result<Self, ParseError> {
+        parse::parse(s, limits, true)
+    }
+}
+
+impl Drop for I18nString {
+    /// Drop nested arguments with an explicit stack, so deep trees do not overflow the stack.
+    fn drop(&mut self) {
+        fn is_shallow(s: &I18nString) -> bool {
+            match s {
+                I18nString::Template(_, args) => args.is_empty(),
+                I18nString::Sensitive(inner) => matches!(**inner, I18nString::Literal(_) | I18nString::Value(_)),
+                _ => true,
+            }
+        }
+
+        fn take_children(s: &mut I18nString, stack: &mut Vec<I18nString>) {
+            match s {
+                I18nString::Template(_, args) => stack.extend(core::mem::take(args).into_vec()),
+                I18nString::Sensitive(inner) => stack.push(core::mem::replace(
+                    &mut **inner,
+                    I18nString::Literal(CompactString::default()),
+                )),
+                _ => {}
+            }
+        }
+
+        let shallow = match &*self {
+            I18nString::Template(_, args) => args.iter().all(is_shallow),
+            s => is_shallow(s),
+        };
+        if shallow {
+            return;
+        }
+
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut s) = stack.pop() {
+            take_children(&mut s, &mut stack);
+        }
+    }
 }
 
 impl FromStr for I18nString {
     type Err = InvalidFormat;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parse::parse(s)
+        parse::parse(s, &Limits::default(), false).map_err(|_| InvalidFormat)
     }
 }
 
 impl Display for I18nString {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        format::format_to(f, self)
+        if f.alternate() {
+            format::format_pretty_to(f, self, None, &Layout::new())
+        } else {
+            format::format_to(f, self, None)
+        }
     }
 }
 
@@ -0,0 +1,92 @@
+use core::fmt::{Display, Formatter};
+
+/// Limits on the size of strings while parsing and translating, to bound the work done for untrusted input.
+///
+/// The depth of a string is the number of nested nodes from the root, e.g. `'a'` has depth 1 and
+/// `t!('{0}', 'a')` has depth 2. While translating, every substituted argument counts as a node,
+/// so a template repeating an argument counts it repeatedly.
+///
+/// By default nothing is limited, so strings of any size parse and translate like they did without limits.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{I18nString, ParseError, limit::{LimitExceeded, Limits}};
+///
+/// let limits = Limits::new().max_depth(2);
+/// assert!(I18nString::parse_with_limits("t!('{0}', 'a')", &limits).is_ok());
+/// assert_eq!(
+///     I18nString::parse_with_limits("t!('{0}', t!('{0}', 'a'))", &limits),
+///     Err(ParseError::LimitExceeded(LimitExceeded::Depth))
+/// );
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Limits {
+    pub(crate) max_depth: usize,
+    pub(crate) max_nodes: usize,
+    pub(crate) max_output_bytes: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_nodes: usize::MAX,
+            max_output_bytes: usize::MAX,
+        }
+    }
+}
+
+impl Limits {
+    /// Create a new `Limits` without any limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the nesting depth.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Limit the number of parsed or translated nodes.
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// Limit the length of the translated output in bytes, parsing is not affected.
+    pub fn max_output_bytes(mut self, max_output_bytes: usize) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
+    }
+}
+
+/// Error type for an exceeded limit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LimitExceeded {
+    /// The nesting depth exceeded [`Limits::max_depth`].
+    Depth,
+    /// The number of nodes exceeded [`Limits::max_nodes`].
+    Nodes,
+    /// The output length exceeded [`Limits::max_output_bytes`].
+    OutputBytes,
+    /// A string read by a `stream::MessageReader` spanned more lines than its `max_lines`.
+    Lines,
+}
+
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LimitExceeded::Depth => write!(f, "nesting depth limit exceeded"),
+            LimitExceeded::Nodes => write!(f, "node count limit exceeded"),
+            LimitExceeded::OutputBytes => write!(f, "output size limit exceeded"),
+            LimitExceeded::Lines => write!(f, "line count limit exceeded"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LimitExceeded {}
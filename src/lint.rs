@@ -0,0 +1,384 @@
+use alloc::{borrow::Cow, collections::BTreeSet, string::String, vec::Vec};
+use core::fmt::{Display, Formatter, Write};
+
+use compact_str::CompactString;
+
+use crate::{
+    catalog::{Catalog, Message},
+    json,
+    template::{self, Segment},
+};
+
+/// Severity of an issue.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Severity {
+    /// The translation works but should be reviewed.
+    Warning,
+    /// The translation is broken.
+    Error,
+}
+
+impl Severity {
+    /// Get the lower case name, e.g. `error`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// Kind of an issue found by [`lint`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum IssueKind {
+    /// The message has no usable translation, it is missing, empty or fuzzy.
+    Untranslated,
+    /// The placeholders of the translation differ from the source.
+    PlaceholderMismatch {
+        /// Placeholders of the source missing in the translation.
+        missing: Vec<usize>,
+        /// Placeholders of the translation not in the source.
+        extra: Vec<usize>,
+    },
+    /// The translation has unpaired braces or invalid placeholders, which are printed as is.
+    UnbalancedBraces,
+    /// The translation is the same as the source.
+    IdenticalToSource,
+    /// The trailing whitespace of the translation differs from the source.
+    TrailingWhitespace,
+    /// Plural forms required by the `Plural-Forms` header are missing or empty.
+    MissingPluralForms {
+        /// Number of plural forms of the translated catalog.
+        expected: usize,
+        /// Indices of the missing forms.
+        missing: Vec<usize>,
+    },
+}
+
+impl IssueKind {
+    /// Get the severity of the issue.
+    pub fn severity(&self) -> Severity {
+        match self {
+            IssueKind::Untranslated | IssueKind::IdenticalToSource | IssueKind::TrailingWhitespace => Severity::Warning,
+            IssueKind::PlaceholderMismatch { .. } | IssueKind::UnbalancedBraces | IssueKind::MissingPluralForms { .. } => {
+                Severity::Error
+            }
+        }
+    }
+
+    /// Get a stable kebab case code of the issue, e.g. `placeholder-mismatch`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            IssueKind::Untranslated => "untranslated",
+            IssueKind::PlaceholderMismatch { .. } => "placeholder-mismatch",
+            IssueKind::UnbalancedBraces => "unbalanced-braces",
+            IssueKind::IdenticalToSource => "identical-to-source",
+            IssueKind::TrailingWhitespace => "trailing-whitespace",
+            IssueKind::MissingPluralForms { .. } => "missing-plural-forms",
+        }
+    }
+}
+
+fn write_indices(f: &mut Formatter<'_>, indices: &[usize], placeholders: bool) -> core::fmt::Result {
+    for (n, index) in indices.iter().enumerate() {
+        if n > 0 {
+            f.write_str(", ")?;
+        }
+        if placeholders {
+            write!(f, "{{{index}}}")?;
+        } else {
+            write!(f, "{index}")?;
+        }
+    }
+    Ok(())
+}
+
+impl Display for IssueKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            IssueKind::Untranslated => f.write_str("untranslated"),
+            IssueKind::PlaceholderMismatch { missing, extra } => {
+                f.write_str("placeholder mismatch")?;
+                if !missing.is_empty() {
+                    f.write_str(", missing ")?;
+                    write_indices(f, missing, true)?;
+                }
+                if !extra.is_empty() {
+                    f.write_str(", unexpected ")?;
+                    write_indices(f, extra, true)?;
+                }
+                Ok(())
+            }
+            IssueKind::UnbalancedBraces => f.write_str("unbalanced braces or invalid placeholder"),
+            IssueKind::IdenticalToSource => f.write_str("identical to source"),
+            IssueKind::TrailingWhitespace => f.write_str("trailing whitespace differs from source"),
+            IssueKind::MissingPluralForms { expected, missing } => {
+                write!(f, "missing plural forms of {expected}: ")?;
+                write_indices(f, missing, false)
+            }
+        }
+    }
+}
+
+/// An issue of a message of a translated catalog.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Issue {
+    /// Context of the message.
+    pub context: Option<CompactString>,
+    /// Id of the message.
+    pub id: CompactString,
+    /// Plural form of the translation, `None` for singular messages and issues of the whole message.
+    pub form: Option<usize>,
+    /// What is wrong.
+    pub kind: IssueKind,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: msgid {:?}", self.kind.severity().name(), self.id)?;
+        if let Some(context) = &self.context {
+            write!(f, " (msgctxt {context:?})")?;
+        }
+        if let Some(form) = self.form {
+            write!(f, " [{form}]")?;
+        }
+        write!(f, ": {}", self.kind)
+    }
+}
+
+/// Placeholder indices of a template and whether it has invalid segments.
+fn placeholders(template: &str) -> (BTreeSet<usize>, bool) {
+    let mut indices = BTreeSet::new();
+    let mut invalid = false;
+    for segment in template::segments(template) {
+        match segment {
+            Segment::Argument { index, .. } => {
+                indices.insert(index);
+            }
+            Segment::Invalid(_) => invalid = true,
+            Segment::Text(_) => {}
+        }
+    }
+    (indices, invalid)
+}
+
+fn trailing_whitespace(s: &str) -> &str {
+    &s[s.trim_end().len()..]
+}
+
+/// Get the source text of a form that translations are compared against.
+///
+/// Messages keyed by id have their text in extracted comments, other messages and plural forms use their
+/// `msgid` and `msgid_plural`.
+fn source_text(source: &Message, form: usize) -> Cow<'_, str> {
+    match (form, &source.id_plural) {
+        (0, _) => source.source_text().map_or(Cow::Borrowed(&source.id), Cow::Owned),
+        (_, Some(id_plural)) => Cow::Borrowed(id_plural),
+        (_, None) => Cow::Borrowed(&source.id),
+    }
+}
+
+fn lint_message(source: &Message, translation: Option<&Message>, plurals: usize, issues: &mut Vec<Issue>) {
+    let mut push = |form: Option<usize>, kind: IssueKind| {
+        issues.push(Issue {
+            context: source.context.clone(),
+            id: source.id.clone(),
+            form,
+            kind,
+        })
+    };
+
+    let translation = match translation {
+        Some(translation) if !translation.fuzzy && translation.translations.iter().any(|t| !t.is_empty()) => translation,
+        _ => return push(None, IssueKind::Untranslated),
+    };
+
+    let plural = source.id_plural.is_some() || translation.id_plural.is_some();
+    let (mut expected, _) = placeholders(&source_text(source, 0));
+    if source.id_plural.is_some() {
+        expected.extend(placeholders(&source_text(source, 1)).0);
+    }
+
+    if plural {
+        let missing: Vec<usize> = (0..plurals.max(translation.translations.len()))
+            .filter(|n| translation.translations.get(*n).is_none_or(|t| t.is_empty()))
+            .collect();
+        if !missing.is_empty() {
+            push(
+                None,
+                IssueKind::MissingPluralForms {
+                    expected: plurals,
+                    missing,
+                },
+            );
+        }
+    }
+
+    let mut found = BTreeSet::new();
+    for (n, text) in translation.translations.iter().enumerate() {
+        if text.is_empty() {
+            continue;
+        }
+        let form = plural.then_some(n);
+        let source_text = source_text(source, n);
+
+        let (indices, invalid) = placeholders(text);
+        if invalid {
+            push(form, IssueKind::UnbalancedBraces);
+        }
+        let extra: Vec<usize> = indices.difference(&expected).copied().collect();
+        // a plural form may leave out placeholders, e.g. "one file" for {0} files
+        let missing: Vec<usize> = if plural {
+            Vec::new()
+        } else {
+            expected.difference(&indices).copied().collect()
+        };
+        if !extra.is_empty() || !missing.is_empty() {
+            push(form, IssueKind::PlaceholderMismatch { missing, extra });
+        }
+        found.extend(indices);
+
+        if *text == *source_text && source_text.chars().any(char::is_alphabetic) {
+            push(form, IssueKind::IdenticalToSource);
+        }
+        if trailing_whitespace(text) != trailing_whitespace(&source_text) {
+            push(form, IssueKind::TrailingWhitespace);
+        }
+    }
+
+    if plural {
+        let missing: Vec<usize> = expected.difference(&found).copied().collect();
+        if !missing.is_empty() {
+            push(
+                None,
+                IssueKind::PlaceholderMismatch {
+                    missing,
+                    extra: Vec::new(),
+                },
+            );
+        }
+    }
+}
+
+/// Check the translations of a translated catalog against the messages of a source catalog.
+///
+/// Placeholders are parsed with the same grammar as translating does. Plural messages are expected to have
+/// as many forms as the `Plural-Forms` header of the translated catalog declares, 2 if there is none, and
+/// each placeholder of the source needs to appear in at least one form.
+///
+/// Translations are compared against the source text, which is the extracted `Source text:` comment of a message
+/// keyed by id, e.g. `err.nf`, and the `msgid` otherwise.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{catalog::Catalog, lint::{IssueKind, lint}};
+///
+/// let source = Catalog::parse_po("msgid \"{0} of {1}\"\nmsgstr \"\"\n\nmsgid \"Cancel\"\nmsgstr \"\"\n").unwrap();
+/// let de = Catalog::parse_po("msgid \"{0} of {1}\"\nmsgstr \"{0} von {2}\"\n").unwrap();
+///
+/// let issues = lint(&source, &de);
+/// assert_eq!(issues[0].id, "Cancel");
+/// assert_eq!(issues[0].kind, IssueKind::Untranslated);
+/// assert_eq!(issues[1].kind, IssueKind::PlaceholderMismatch { missing: vec![1], extra: vec![2] });
+/// assert_eq!(issues[1].to_string(), "error: msgid \"{0} of {1}\": placeholder mismatch, missing {1}, unexpected {2}");
+/// ```
+pub fn lint(source: &Catalog, translated: &Catalog) -> Vec<Issue> {
+    let plurals = translated.nplurals().unwrap_or(2);
+    let mut issues = Vec::new();
+    for message in source.messages() {
+        let translation = translated.get(message.context.as_deref(), &message.id);
+        lint_message(message, translation, plurals, &mut issues);
+    }
+    issues
+}
+
+/// Write issues as a JSON array.
+///
+/// Each issue is an object with the `severity`, `code`, `context`, `id`, `form` and human readable `message`
+/// fields, plus `missing` and `extra` for placeholder mismatches and `expected` and `missing` for missing
+/// plural forms.
+pub fn write_json<W: Write>(output: &mut W, issues: &[Issue]) -> core::fmt::Result {
+    fn write_array<W: Write>(output: &mut W, values: &[usize]) -> core::fmt::Result {
+        output.write_char('[')?;
+        for (n, value) in values.iter().enumerate() {
+            if n > 0 {
+                output.write_char(',')?;
+            }
+            write!(output, "{value}")?;
+        }
+        output.write_char(']')
+    }
+
+    output.write_char('[')?;
+    for (n, issue) in issues.iter().enumerate() {
+        if n > 0 {
+            output.write_char(',')?;
+        }
+        write!(
+            output,
+            "{{\"severity\":\"{}\",\"code\":\"{}\",\"context\":",
+            issue.kind.severity().name(),
+            issue.kind.code()
+        )?;
+        json::write_optional_string(output, issue.context.as_deref())?;
+        output.write_str(",\"id\":")?;
+        json::write_string(output, &issue.id)?;
+        match issue.form {
+            Some(form) => write!(output, ",\"form\":{form}")?,
+            None => output.write_str(",\"form\":null")?,
+        }
+        output.write_str(",\"message\":")?;
+        let mut message = String::new();
+        write!(message, "{}", issue.kind)?;
+        json::write_string(output, &message)?;
+        match &issue.kind {
+            IssueKind::PlaceholderMismatch { missing, extra } => {
+                output.write_str(",\"missing\":")?;
+                write_array(output, missing)?;
+                output.write_str(",\"extra\":")?;
+                write_array(output, extra)?;
+            }
+            IssueKind::MissingPluralForms { expected, missing } => {
+                write!(output, ",\"expected\":{expected},\"missing\":")?;
+                write_array(output, missing)?;
+            }
+            _ => {}
+        }
+        output.write_char('}')?;
+    }
+    output.write_char(']')
+}
+
+/// Write the issues of several translated catalogs as a JSON object, keyed by the name of each catalog.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{catalog::Catalog, lint::{lint, write_json_reports}};
+///
+/// let source = Catalog::parse_po("msgid \"Cancel\"\nmsgstr \"\"\n").unwrap();
+/// let issues = lint(&source, &Catalog::new());
+/// let mut output = String::new();
+/// write_json_reports(&mut output, [("de.po", &issues[..]), ("fr.po", &[])]).unwrap();
+/// assert!(output.starts_with("{\"de.po\":[{\"severity\":\"warning\",\"code\":\"untranslated\""));
+/// assert!(output.ends_with("}],\"fr.po\":[]}"));
+/// ```
+pub fn write_json_reports<'a, W: Write, I: IntoIterator<Item = (&'a str, &'a [Issue])>>(
+    output: &mut W,
+    reports: I,
+) -> core::fmt::Result {
+    output.write_char('{')?;
+    for (n, (name, issues)) in reports.into_iter().enumerate() {
+        if n > 0 {
+            output.write_char(',')?;
+        }
+        json::write_string(output, name)?;
+        output.write_char(':')?;
+        write_json(output, issues)?;
+    }
+    output.write_char('}')
+}
@@ -0,0 +1,373 @@
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use compact_str::CompactString;
+
+use crate::{InvalidFormat, bidi::Direction};
+
+/// A BCP 47 language tag, e.g. `zh-Hant-TW` or `en-US-u-nu-latn`.
+///
+/// Tags are canonicalized while parsing: `_` separators are accepted, subtags are case normalized
+/// and deprecated language codes are replaced (`iw` becomes `he`).
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::locale::Locale;
+///
+/// let locale: Locale = "zh_hant_tw".parse().unwrap();
+/// assert_eq!(locale.to_string(), "zh-Hant-TW");
+/// assert_eq!(locale.language(), "zh");
+/// assert_eq!(locale.script(), Some("Hant"));
+/// assert_eq!(locale.region(), Some("TW"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Locale {
+    language: CompactString,
+    script: Option<CompactString>,
+    region: Option<CompactString>,
+    variants: Vec<CompactString>,
+    extensions: Option<CompactString>,
+}
+
+fn is_alpha(s: &str, len: core::ops::RangeInclusive<usize>) -> bool {
+    len.contains(&s.len()) && s.bytes().all(|c| c.is_ascii_alphabetic())
+}
+
+fn is_alphanumeric(s: &str, len: core::ops::RangeInclusive<usize>) -> bool {
+    len.contains(&s.len()) && s.bytes().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn canonical_language(language: &str) -> &str {
+    const ALIASES: [(&str, &str); 6] = [
+        ("in", "id"),
+        ("iw", "he"),
+        ("ji", "yi"),
+        ("jw", "jv"),
+        ("mo", "ro"),
+        ("root", "und"),
+    ];
+
+    ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == language)
+        .map_or(language, |(_, canonical)| canonical)
+}
+
+impl Locale {
+    /// Get the language subtag, `und` if undetermined.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Get the script subtag.
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// Get the region subtag.
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// Get the variant subtags.
+    pub fn variants(&self) -> impl Iterator<Item = &str> {
+        self.variants.iter().map(|v| v.as_str())
+    }
+
+    /// Get the value of a Unicode extension keyword, e.g. `nu` of `ar-u-nu-latn`.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::locale::Locale;
+    ///
+    /// let locale: Locale = "ar-EG-u-ca-islamic-nu-latn".parse().unwrap();
+    /// assert_eq!(locale.unicode_keyword("nu"), Some("latn"));
+    /// assert_eq!(locale.unicode_keyword("ca"), Some("islamic"));
+    /// assert_eq!(locale.unicode_keyword("hc"), None);
+    /// ```
+    pub fn unicode_keyword(&self, key: &str) -> Option<&str> {
+        let extensions = self.extensions.as_deref()?;
+
+        let mut in_unicode = false;
+        let mut value: Option<(usize, Option<usize>)> = None;
+        let mut offset = 0;
+        for subtag in extensions.split('-') {
+            let start = offset;
+            offset += subtag.len() + 1;
+            match subtag.len() {
+                _ if value.is_some() && subtag.len() <= 2 => break,
+                1 => in_unicode = subtag == "u",
+                2 if in_unicode && subtag == key => value = Some((offset, None)),
+                _ => {
+                    if let Some((_, end)) = &mut value {
+                        *end = Some(start + subtag.len());
+                    }
+                }
+            }
+        }
+
+        match value? {
+            (start, Some(end)) => Some(&extensions[start..end]),
+            (_, None) => Some("true"),
+        }
+    }
+
+    /// Get the text direction of the locale's language.
+    pub fn direction(&self) -> Direction {
+        if self
+            .script
+            .as_deref()
+            .is_some_and(|s| matches!(s, "Arab" | "Hebr" | "Thaa" | "Syrc" | "Nkoo" | "Adlm"))
+        {
+            Direction::Rtl
+        } else if self.script.is_some() {
+            Direction::Ltr
+        } else {
+            Direction::from_language(&self.language)
+        }
+    }
+
+    /// Get the script, or the likely script for languages commonly written in several scripts, e.g. `Hant` for `zh-TW`.
+    fn likely_script(&self) -> Option<&str> {
+        if let Some(script) = &self.script {
+            return Some(script);
+        }
+        Some(match (self.language.as_str(), self.region.as_deref()) {
+            ("zh", Some("TW" | "HK" | "MO")) => "Hant",
+            ("zh", _) => "Hans",
+            ("sr", Some("ME")) => "Latn",
+            ("sr", _) => "Cyrl",
+            ("bs", _) => "Latn",
+            ("az", Some("IR")) => "Arab",
+            ("az", _) => "Latn",
+            ("uz", Some("AF")) => "Arab",
+            ("uz", _) => "Latn",
+            ("pa", Some("PK")) => "Arab",
+            ("pa", _) => "Guru",
+            ("mn", Some("CN")) => "Mong",
+            ("mn", _) => "Cyrl",
+            _ => return None,
+        })
+    }
+
+    /// Get the locale without extensions.
+    pub fn without_extensions(&self) -> Self {
+        Self {
+            extensions: None,
+            ..self.clone()
+        }
+    }
+
+    /// Get the parent locale, by removing the last variant, the region or the script in turn.
+    ///
+    /// Extensions are dropped, a locale with only a language has no parent.
+    pub fn parent(&self) -> Option<Self> {
+        let mut parent = self.without_extensions();
+        if parent.variants.pop().is_some() || parent.region.take().is_some() || parent.script.take().is_some() {
+            Some(parent)
+        } else {
+            None
+        }
+    }
+
+    /// Iterate the locale without extensions and all its parents, from the most specific one.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::locale::Locale;
+    ///
+    /// let locale: Locale = "sr-Latn-RS-u-nu-latn".parse().unwrap();
+    /// let chain: Vec<String> = locale.fallback_chain().map(|l| l.to_string()).collect();
+    /// assert_eq!(chain, ["sr-Latn-RS", "sr-Latn", "sr"]);
+    /// ```
+    pub fn fallback_chain(&self) -> impl Iterator<Item = Locale> {
+        core::iter::successors(Some(self.without_extensions()), Locale::parent)
+    }
+}
+
+impl FromStr for Locale {
+    type Err = InvalidFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut subtags = s.split(['-', '_']).peekable();
+
+        let language = subtags.next().ok_or(InvalidFormat)?.to_ascii_lowercase();
+        if !(is_alpha(&language, 2..=3) || is_alpha(&language, 5..=8) || language == "root") {
+            return Err(InvalidFormat);
+        }
+        let mut locale = Locale {
+            language: canonical_language(&language).into(),
+            script: None,
+            region: None,
+            variants: Vec::new(),
+            extensions: None,
+        };
+
+        if let Some(script) = subtags.next_if(|s| is_alpha(s, 4..=4)) {
+            let mut script = script.to_ascii_lowercase();
+            script[..1].make_ascii_uppercase();
+            locale.script = Some(script.into());
+        }
+        if let Some(region) = subtags.next_if(|s| is_alpha(s, 2..=2) || (s.len() == 3 && s.bytes().all(|c| c.is_ascii_digit()))) {
+            locale.region = Some(region.to_ascii_uppercase().into());
+        }
+        while let Some(variant) = subtags.next_if(|s| {
+            is_alphanumeric(s, 5..=8) || (s.len() == 4 && s.as_bytes()[0].is_ascii_digit() && is_alphanumeric(s, 4..=4))
+        }) {
+            locale.variants.push(variant.to_ascii_lowercase().into());
+        }
+
+        let mut extensions = String::new();
+        let mut expect_subtag = false;
+        let mut private_use = false;
+        for subtag in subtags {
+            let subtag = subtag.to_ascii_lowercase();
+            if subtag.len() == 1 && !private_use && is_alphanumeric(&subtag, 1..=1) {
+                if expect_subtag {
+                    return Err(InvalidFormat);
+                }
+                private_use = subtag == "x";
+                expect_subtag = true;
+            } else if is_alphanumeric(&subtag, if private_use { 1..=8 } else { 2..=8 }) {
+                expect_subtag = false;
+            } else {
+                return Err(InvalidFormat);
+            }
+            if !extensions.is_empty() {
+                extensions.push('-');
+            }
+            extensions.push_str(&subtag);
+        }
+        if expect_subtag {
+            return Err(InvalidFormat);
+        }
+        if !extensions.is_empty() {
+            locale.extensions = Some(extensions.into());
+        }
+
+        Ok(locale)
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.language)?;
+        for subtag in self
+            .script
+            .iter()
+            .chain(&self.region)
+            .chain(&self.variants)
+            .chain(&self.extensions)
+        {
+            write!(f, "-{subtag}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Pick the best available locale for a list of requested locales in priority order.
+///
+/// For each requested locale, its fallback chain is searched for an exact match first,
+/// then any available locale with the same language is accepted. Both only match locales with the same script,
+/// where a missing script is inferred for languages written in several scripts, e.g. `zh-TW` is `Hant`.
+/// Extensions are ignored while matching.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::locale::{Locale, negotiate};
+///
+/// let available: Vec<Locale> = ["en", "fr-FR", "zh-Hans"].iter().map(|s| s.parse().unwrap()).collect();
+/// let requested: Vec<Locale> = ["fr-CH", "en"].iter().map(|s| s.parse().unwrap()).collect();
+/// assert_eq!(negotiate(&requested, &available).unwrap().to_string(), "fr-FR");
+///
+/// let requested: Vec<Locale> = ["zh-Hans-CN"].iter().map(|s| s.parse().unwrap()).collect();
+/// assert_eq!(negotiate(&requested, &available).unwrap().to_string(), "zh-Hans");
+///
+/// let requested: Vec<Locale> = ["zh-TW"].iter().map(|s| s.parse().unwrap()).collect();
+/// assert_eq!(negotiate(&requested, &available), None);
+/// ```
+pub fn negotiate<'a>(requested: &[Locale], available: &'a [Locale]) -> Option<&'a Locale> {
+    for locale in requested {
+        let same_script = |a: &Locale| match (a.likely_script(), locale.likely_script()) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        };
+        for candidate in locale.fallback_chain() {
+            if let Some(found) = available
+                .iter()
+                .find(|a| a.without_extensions() == candidate && same_script(a))
+            {
+                return Some(found);
+            }
+        }
+
+        if let Some(found) = available.iter().find(|a| a.language == locale.language && same_script(a)) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// Parse an HTTP `Accept-Language` header into locales ordered by quality.
+///
+/// Wildcards, invalid tags and tags with zero quality are skipped.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::locale::parse_accept_language;
+///
+/// let locales = parse_accept_language("en;q=0.8, de-CH, de;q=0.9, *;q=0.5, fr;q=0");
+/// let tags: Vec<String> = locales.iter().map(|l| l.to_string()).collect();
+/// assert_eq!(tags, ["de-CH", "de", "en"]);
+/// ```
+pub fn parse_accept_language(header: &str) -> Vec<Locale> {
+    let mut locales: Vec<(u16, Locale)> = Vec::new();
+    for item in header.split(',') {
+        let mut params = item.split(';');
+        let tag = params.next().unwrap_or_default().trim();
+        let mut quality = 1000;
+        for param in params {
+            if let Some(q) = param.trim().strip_prefix("q=") {
+                quality = parse_quality(q.trim()).unwrap_or(0);
+            }
+        }
+        if quality == 0 || tag == "*" {
+            continue;
+        }
+        if let Ok(locale) = Locale::from_str(tag) {
+            locales.push((quality, locale));
+        }
+    }
+    locales.sort_by_key(|(quality, _)| core::cmp::Reverse(*quality));
+    locales.into_iter().map(|(_, locale)| locale).collect()
+}
+
+/// Parse a quality value into thousandths.
+fn parse_quality(q: &str) -> Option<u16> {
+    let (int, frac) = q.split_once('.').unwrap_or((q, ""));
+    if frac.len() > 3 || !frac.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut value: u16 = match int {
+        "0" => 0,
+        "1" => 1000,
+        _ => return None,
+    };
+    for (idx, c) in frac.bytes().enumerate() {
+        value += (c - b'0') as u16 * [100, 10, 1][idx];
+    }
+    (value <= 1000).then_some(value)
+}
@@ -0,0 +1,348 @@
+use crate::{
+    locale::Locale,
+    number::{NumberFormat, NumberingSystem},
+};
+
+const fn number(decimal: &'static str, group: &'static str) -> NumberFormat {
+    NumberFormat {
+        decimal,
+        group,
+        ..NumberFormat::ROOT
+    }
+}
+
+const fn indian(numbering_system: NumberingSystem) -> NumberFormat {
+    NumberFormat {
+        secondary_grouping: 2,
+        numbering_system,
+        ..NumberFormat::ROOT
+    }
+}
+
+/// Formatting data of a locale, extracted from CLDR.
+///
+/// Patterns use `#` for the formatted number and `¤` for the currency symbol,
+/// date and time patterns use a subset of the CLDR date field symbols (`y`, `M`, `d`, `H`, `h`, `m`, `s`, `a`).
+#[derive(Debug, Copy, Clone)]
+pub struct LocaleData {
+    pub tag: &'static str,
+    pub number: NumberFormat,
+    pub percent: &'static str,
+    pub currency: &'static str,
+    pub date: &'static str,
+    pub time: &'static str,
+    pub date_time: &'static str,
+    pub am_pm: [&'static str; 2],
+}
+
+pub const ROOT: LocaleData = LocaleData {
+    tag: "und",
+    number: NumberFormat::ROOT,
+    percent: "#%",
+    currency: "¤\u{a0}#",
+    date: "y-MM-dd",
+    time: "HH:mm:ss",
+    date_time: "{1} {0}",
+    am_pm: ["AM", "PM"],
+};
+
+static LOCALES: &[LocaleData] = &[
+    LocaleData {
+        tag: "ar",
+        number: NumberFormat {
+            decimal: "٫",
+            group: "٬",
+            minus: "\u{61c}-",
+            numbering_system: NumberingSystem::Arab,
+            ..NumberFormat::ROOT
+        },
+        percent: "#٪\u{61c}",
+        currency: "\u{200f}#\u{a0}¤",
+        date: "d\u{200f}/M\u{200f}/y",
+        time: "h:mm:ss\u{a0}a",
+        date_time: "{1}، {0}",
+        am_pm: ["ص", "م"],
+    },
+    LocaleData {
+        tag: "bn",
+        number: indian(NumberingSystem::Beng),
+        percent: "#%",
+        currency: "#¤",
+        date: "d/M/y",
+        time: "h:mm:ss\u{a0}a",
+        date_time: "{1}, {0}",
+        am_pm: ["AM", "PM"],
+    },
+    LocaleData {
+        tag: "de",
+        number: number(",", "."),
+        percent: "#\u{a0}%",
+        currency: "#\u{a0}¤",
+        date: "dd.MM.y",
+        time: "HH:mm:ss",
+        date_time: "{1}, {0}",
+        am_pm: ["AM", "PM"],
+    },
+    LocaleData {
+        tag: "en",
+        number: number(".", ","),
+        percent: "#%",
+        currency: "¤#",
+        date: "M/d/y",
+        time: "h:mm:ss\u{202f}a",
+        date_time: "{1}, {0}",
+        am_pm: ["AM", "PM"],
+    },
+    LocaleData {
+        tag: "en-GB",
+        number: number(".", ","),
+        percent: "#%",
+        currency: "¤#",
+        date: "dd/MM/y",
+        time: "HH:mm:ss",
+        date_time: "{1}, {0}",
+        am_pm: ["am", "pm"],
+    },
+    LocaleData {
+        tag: "en-IN",
+        number: indian(NumberingSystem::Latn),
+        percent: "#%",
+        currency: "¤#",
+        date: "d/M/y",
+        time: "h:mm:ss\u{a0}a",
+        date_time: "{1}, {0}",
+        am_pm: ["am", "pm"],
+    },
+    LocaleData {
+        tag: "es",
+        number: NumberFormat {
+            min_grouping: 2,
+            ..number(",", ".")
+        },
+        percent: "#\u{a0}%",
+        currency: "#\u{a0}¤",
+        date: "d/M/y",
+        time: "H:mm:ss",
+        date_time: "{1}, {0}",
+        am_pm: ["a.\u{a0}m.", "p.\u{a0}m."],
+    },
+    LocaleData {
+        tag: "fa",
+        number: NumberFormat {
+            decimal: "٫",
+            group: "٬",
+            minus: "\u{200e}\u{2212}",
+            numbering_system: NumberingSystem::Arabext,
+            ..NumberFormat::ROOT
+        },
+        percent: "#٪",
+        currency: "\u{200e}¤#",
+        date: "y/M/d",
+        time: "H:mm:ss",
+        date_time: "{1}،\u{200f} {0}",
+        am_pm: ["ق.ظ.", "ب.ظ."],
+    },
+    LocaleData {
+        tag: "fr",
+        number: number(",", "\u{202f}"),
+        percent: "#\u{a0}%",
+        currency: "#\u{a0}¤",
+        date: "dd/MM/y",
+        time: "HH:mm:ss",
+        date_time: "{1} {0}",
+        am_pm: ["AM", "PM"],
+    },
+    LocaleData {
+        tag: "he",
+        number: number(".", ","),
+        percent: "#%",
+        currency: "\u{200f}#\u{a0}¤",
+        date: "d.M.y",
+        time: "H:mm:ss",
+        date_time: "{1}, {0}",
+        am_pm: ["לפנה״צ", "אחה״צ"],
+    },
+    LocaleData {
+        tag: "hi",
+        number: indian(NumberingSystem::Latn),
+        percent: "#%",
+        currency: "¤#",
+        date: "d/M/y",
+        time: "h:mm:ss\u{a0}a",
+        date_time: "{1}, {0}",
+        am_pm: ["am", "pm"],
+    },
+    LocaleData {
+        tag: "it",
+        number: number(",", "."),
+        percent: "#%",
+        currency: "#\u{a0}¤",
+        date: "dd/MM/y",
+        time: "HH:mm:ss",
+        date_time: "{1}, {0}",
+        am_pm: ["AM", "PM"],
+    },
+    LocaleData {
+        tag: "ja",
+        number: number(".", ","),
+        percent: "#%",
+        currency: "¤#",
+        date: "y/MM/dd",
+        time: "H:mm:ss",
+        date_time: "{1} {0}",
+        am_pm: ["午前", "午後"],
+    },
+    LocaleData {
+        tag: "ko",
+        number: number(".", ","),
+        percent: "#%",
+        currency: "¤#",
+        date: "y. M. d.",
+        time: "a h:mm:ss",
+        date_time: "{1} {0}",
+        am_pm: ["오전", "오후"],
+    },
+    LocaleData {
+        tag: "mr",
+        number: indian(NumberingSystem::Deva),
+        percent: "#%",
+        currency: "¤#",
+        date: "d/M/y",
+        time: "h:mm:ss\u{a0}a",
+        date_time: "{1}, {0}",
+        am_pm: ["AM", "PM"],
+    },
+    LocaleData {
+        tag: "ne",
+        number: indian(NumberingSystem::Deva),
+        percent: "#%",
+        currency: "¤\u{a0}#",
+        date: "y-MM-dd",
+        time: "HH:mm:ss",
+        date_time: "{1}, {0}",
+        am_pm: ["पूर्वाह्न", "अपराह्न"],
+    },
+    LocaleData {
+        tag: "nl",
+        number: number(",", "."),
+        percent: "#%",
+        currency: "¤\u{a0}#",
+        date: "dd-MM-y",
+        time: "HH:mm:ss",
+        date_time: "{1}, {0}",
+        am_pm: ["a.m.", "p.m."],
+    },
+    LocaleData {
+        tag: "pl",
+        number: NumberFormat {
+            min_grouping: 2,
+            ..number(",", "\u{a0}")
+        },
+        percent: "#%",
+        currency: "#\u{a0}¤",
+        date: "d.MM.y",
+        time: "HH:mm:ss",
+        date_time: "{1}, {0}",
+        am_pm: ["AM", "PM"],
+    },
+    LocaleData {
+        tag: "pt",
+        number: number(",", "."),
+        percent: "#%",
+        currency: "¤\u{a0}#",
+        date: "dd/MM/y",
+        time: "HH:mm:ss",
+        date_time: "{1}, {0}",
+        am_pm: ["AM", "PM"],
+    },
+    LocaleData {
+        tag: "ru",
+        number: number(",", "\u{a0}"),
+        percent: "#\u{a0}%",
+        currency: "#\u{a0}¤",
+        date: "dd.MM.y",
+        time: "HH:mm:ss",
+        date_time: "{1}, {0}",
+        am_pm: ["AM", "PM"],
+    },
+    LocaleData {
+        tag: "sv",
+        number: NumberFormat {
+            minus: "\u{2212}",
+            ..number(",", "\u{a0}")
+        },
+        percent: "#\u{a0}%",
+        currency: "#\u{a0}¤",
+        date: "y-MM-dd",
+        time: "HH:mm:ss",
+        date_time: "{1} {0}",
+        am_pm: ["fm", "em"],
+    },
+    LocaleData {
+        tag: "tr",
+        number: number(",", "."),
+        percent: "%#",
+        currency: "¤#",
+        date: "dd.MM.y",
+        time: "HH:mm:ss",
+        date_time: "{1} {0}",
+        am_pm: ["ÖÖ", "ÖS"],
+    },
+    LocaleData {
+        tag: "zh",
+        number: number(".", ","),
+        percent: "#%",
+        currency: "¤#",
+        date: "y/M/d",
+        time: "HH:mm:ss",
+        date_time: "{1} {0}",
+        am_pm: ["上午", "下午"],
+    },
+];
+
+/// Find the data of the most specific bundled locale, falling back to the root locale.
+///
+/// The `nu` Unicode extension keyword overrides the numbering system of the locale.
+pub fn lookup(locale: &Locale) -> LocaleData {
+    let mut data = LOCALES
+        .iter()
+        .filter(|data| {
+            let (language, region) = match data.tag.split_once('-') {
+                Some((language, region)) => (language, Some(region)),
+                None => (data.tag, None),
+            };
+            language == locale.language() && region.is_none_or(|r| Some(r) == locale.region())
+        })
+        .max_by_key(|data| data.tag.len())
+        .copied()
+        .unwrap_or(ROOT);
+
+    if let Some(numbering_system) = locale.unicode_keyword("nu").and_then(NumberingSystem::from_name) {
+        data.number.numbering_system = numbering_system;
+    }
+
+    data
+}
+
+/// Get the symbol and default number of fraction digits of an ISO 4217 currency code.
+pub fn currency(code: &str) -> (&str, usize) {
+    const CURRENCIES: &[(&str, &str, usize)] = &[
+        ("BRL", "R$", 2),
+        ("CNY", "CN¥", 2),
+        ("EUR", "€", 2),
+        ("GBP", "£", 2),
+        ("ILS", "₪", 2),
+        ("INR", "₹", 2),
+        ("JPY", "¥", 0),
+        ("KRW", "₩", 0),
+        ("RUB", "₽", 2),
+        ("TRY", "₺", 2),
+        ("USD", "$", 2),
+        ("VND", "₫", 0),
+    ];
+
+    match CURRENCIES.iter().find(|(c, _, _)| c.eq_ignore_ascii_case(code)) {
+        Some((_, symbol, digits)) => (symbol, *digits),
+        None => (code, 2),
+    }
+}
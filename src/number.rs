@@ -0,0 +1,258 @@
+use alloc::string::String;
+
+use crate::{locale::Locale, locale_data, value::Decimal};
+
+/// A numbering system, the set of digits used to write numbers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum NumberingSystem {
+    /// Latin digits `0123456789`.
+    Latn,
+    /// Arabic-Indic digits `٠١٢٣٤٥٦٧٨٩`.
+    Arab,
+    /// Extended Arabic-Indic digits `۰۱۲۳۴۵۶۷۸۹`.
+    Arabext,
+    /// Bengali digits `০১২৩৪৫৬৭৮৯`.
+    Beng,
+    /// Devanagari digits `०१२३४५६७८९`.
+    Deva,
+    /// Myanmar digits `၀၁၂၃၄၅၆၇၈၉`.
+    Mymr,
+    /// Thai digits `๐๑๒๓๔๕๖๗๘๙`.
+    Thai,
+}
+
+impl NumberingSystem {
+    /// Get a numbering system from its CLDR name, e.g. `deva`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        const NAMES: [(&str, NumberingSystem); 7] = [
+            ("latn", NumberingSystem::Latn),
+            ("arab", NumberingSystem::Arab),
+            ("arabext", NumberingSystem::Arabext),
+            ("beng", NumberingSystem::Beng),
+            ("deva", NumberingSystem::Deva),
+            ("mymr", NumberingSystem::Mymr),
+            ("thai", NumberingSystem::Thai),
+        ];
+
+        NAMES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, system)| *system)
+    }
+
+    /// Get the CLDR name of the numbering system.
+    pub fn name(self) -> &'static str {
+        match self {
+            NumberingSystem::Latn => "latn",
+            NumberingSystem::Arab => "arab",
+            NumberingSystem::Arabext => "arabext",
+            NumberingSystem::Beng => "beng",
+            NumberingSystem::Deva => "deva",
+            NumberingSystem::Mymr => "mymr",
+            NumberingSystem::Thai => "thai",
+        }
+    }
+
+    fn zero(self) -> char {
+        match self {
+            NumberingSystem::Latn => '0',
+            NumberingSystem::Arab => '\u{0660}',
+            NumberingSystem::Arabext => '\u{06F0}',
+            NumberingSystem::Beng => '\u{09E6}',
+            NumberingSystem::Deva => '\u{0966}',
+            NumberingSystem::Mymr => '\u{1040}',
+            NumberingSystem::Thai => '\u{0E50}',
+        }
+    }
+
+    /// Get the digit for a value in `0..10`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is 10 or greater.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::number::NumberingSystem;
+    ///
+    /// assert_eq!(NumberingSystem::Deva.digit(7), '७');
+    /// ```
+    pub fn digit(self, value: u8) -> char {
+        assert!(value < 10, "digit value out of range");
+        char::from_u32(self.zero() as u32 + value as u32).unwrap_or('0')
+    }
+
+    /// Append `input` to `output`, replacing ASCII digits with the digits of this numbering system.
+    pub fn push_digits(self, output: &mut String, input: &str) {
+        for c in input.chars() {
+            match c {
+                '0'..='9' => output.push(self.digit(c as u8 - b'0')),
+                _ => output.push(c),
+            }
+        }
+    }
+}
+
+/// Number symbols, grouping and digits of a locale.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{locale::Locale, number::NumberFormat, value::Decimal};
+///
+/// let format = |tag: &str| NumberFormat::for_locale(&tag.parse::<Locale>().unwrap());
+/// let number = Decimal::new(12345678, 1);
+/// assert_eq!(format("en").format(number), "1,234,567.8");
+/// assert_eq!(format("de").format(number), "1.234.567,8");
+/// assert_eq!(format("hi").format(number), "12,34,567.8");
+/// assert_eq!(format("mr").format(12345), "१२,३४५");
+/// assert_eq!(format("hi-u-nu-deva").format(12345), "१२,३४५");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct NumberFormat {
+    pub(crate) decimal: &'static str,
+    pub(crate) group: &'static str,
+    pub(crate) minus: &'static str,
+    pub(crate) primary_grouping: u8,
+    pub(crate) secondary_grouping: u8,
+    pub(crate) min_grouping: u8,
+    pub(crate) numbering_system: NumberingSystem,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::ROOT
+    }
+}
+
+impl NumberFormat {
+    pub(crate) const ROOT: NumberFormat = NumberFormat {
+        decimal: ".",
+        group: ",",
+        minus: "-",
+        primary_grouping: 3,
+        secondary_grouping: 3,
+        min_grouping: 1,
+        numbering_system: NumberingSystem::Latn,
+    };
+
+    /// Get the number format of the most specific bundled locale.
+    ///
+    /// Unknown locales use the root format, `1,234.5` with latin digits.
+    /// The numbering system can be overridden with the `nu` Unicode extension keyword, e.g. `hi-u-nu-deva`.
+    pub fn for_locale(locale: &Locale) -> Self {
+        locale_data::lookup(locale).number
+    }
+
+    /// Use another numbering system for digits.
+    pub fn with_numbering_system(mut self, numbering_system: NumberingSystem) -> Self {
+        self.numbering_system = numbering_system;
+        self
+    }
+
+    /// Get the decimal separator.
+    pub fn decimal_separator(&self) -> &'static str {
+        self.decimal
+    }
+
+    /// Get the grouping separator.
+    pub fn group_separator(&self) -> &'static str {
+        self.group
+    }
+
+    /// Get the numbering system.
+    pub fn numbering_system(&self) -> NumberingSystem {
+        self.numbering_system
+    }
+
+    /// Format a number.
+    pub fn format<D: Into<Decimal>>(&self, number: D) -> String {
+        let mut output = String::new();
+        self.format_to(&mut output, number.into(), None);
+        output
+    }
+
+    /// Format a number into `output`, rounding half away from zero to `precision` fraction digits if given.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{number::NumberFormat, value::Decimal};
+    ///
+    /// let mut output = String::new();
+    /// NumberFormat::for_locale(&"ar".parse().unwrap()).format_to(&mut output, Decimal::new(-31415, 4), Some(2));
+    /// assert_eq!(output, "\u{61c}-٣٫١٤");
+    /// ```
+    pub fn format_to(&self, output: &mut String, number: Decimal, precision: Option<usize>) {
+        let number = Fixed::new(number.mantissa() as i128, number.scale(), precision);
+        if number.negative {
+            output.push_str(self.minus);
+        }
+        self.push_unsigned(output, &number);
+    }
+
+    pub(crate) fn push_unsigned(&self, output: &mut String, number: &Fixed) {
+        let int = &number.int;
+        let primary = self.primary_grouping as usize;
+        let secondary = self.secondary_grouping as usize;
+        let grouped = int.len() >= primary + self.min_grouping as usize;
+        for (idx, c) in int.char_indices() {
+            let remaining = int.len() - idx;
+            if grouped && idx > 0 && remaining >= primary && (remaining - primary).is_multiple_of(secondary) {
+                output.push_str(self.group);
+            }
+            output.push(self.numbering_system.digit(c as u8 - b'0'));
+        }
+        if !number.frac.is_empty() {
+            output.push_str(self.decimal);
+            self.numbering_system.push_digits(output, &number.frac);
+        }
+    }
+}
+
+/// A fixed-point number split into integer and fraction digits.
+pub(crate) struct Fixed {
+    pub negative: bool,
+    pub int: String,
+    pub frac: String,
+}
+
+impl Fixed {
+    /// Split `mantissa * 10^-scale`, rounding half away from zero to `precision` fraction digits.
+    pub fn new(mantissa: i128, scale: u32, precision: Option<usize>) -> Self {
+        let mut abs = mantissa.unsigned_abs();
+        let mut scale = scale as usize;
+        if let Some(precision) = precision
+            && precision < scale
+        {
+            let divisor = 10u128.pow((scale - precision) as u32);
+            abs = (abs + divisor / 2) / divisor;
+            scale = precision;
+        }
+
+        let mut int = String::new();
+        let mut frac = String::new();
+        for _ in 0..scale {
+            frac.insert(0, char::from(b'0' + (abs % 10) as u8));
+            abs /= 10;
+        }
+        loop {
+            int.insert(0, char::from(b'0' + (abs % 10) as u8));
+            abs /= 10;
+            if abs == 0 {
+                break;
+            }
+        }
+        while frac.len() < precision.unwrap_or(0) {
+            frac.push('0');
+        }
+
+        let negative = mantissa < 0 && int.bytes().chain(frac.bytes()).any(|c| c != b'0');
+        Self { negative, int, frac }
+    }
+}
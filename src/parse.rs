@@ -1,15 +1,34 @@
 use alloc::{string::String, vec::Vec};
+use core::str::FromStr;
 
-use crate::{I18nString, InvalidFormat};
+use compact_str::CompactString;
+
+use crate::{
+    I18nString, InvalidFormat, ParseError, TemplateKey,
+    escape::unescape_sequence,
+    limit::{LimitExceeded, Limits},
+    value::{Decimal, Value},
+};
 
 struct Parser<'s> {
     input: &'s str,
     cursor: usize,
+    limits: &'s Limits,
+    nodes: usize,
+    strict: bool,
+    multiline: bool,
 }
 
 impl<'s> Parser<'s> {
-    fn new(input: &'s str) -> Self {
-        Self { input, cursor: 0 }
+    fn new(input: &'s str, limits: &'s Limits, strict: bool) -> Self {
+        Self {
+            input,
+            cursor: 0,
+            limits,
+            nodes: 0,
+            strict,
+            multiline: true,
+        }
     }
 
     fn peek_char(&self) -> Option<char> {
@@ -27,11 +46,11 @@ impl<'s> Parser<'s> {
     }
 
     fn match_char(&mut self, target: char) -> bool {
-        if let Some(c) = self.peek_char() {
-            if c == target {
-                self.cursor += c.len_utf8();
-                return true;
-            }
+        if let Some(c) = self.peek_char()
+            && c == target
+        {
+            self.cursor += c.len_utf8();
+            return true;
         }
         false
     }
@@ -40,93 +59,233 @@ impl<'s> Parser<'s> {
         if self.match_char(target) { Ok(()) } else { Err(InvalidFormat) }
     }
 
-    fn expect_str(&mut self, target: &str) -> Result<(), InvalidFormat> {
+    fn match_str(&mut self, target: &str) -> bool {
         if self.input[self.cursor..].starts_with(target) {
             self.cursor += target.len();
-            Ok(())
-        } else {
-            Err(InvalidFormat)
+            return true;
+        }
+        false
+    }
+
+    fn expect_str(&mut self, target: &str) -> Result<(), InvalidFormat> {
+        if self.match_str(target) { Ok(()) } else { Err(InvalidFormat) }
+    }
+
+    /// Check whether a literal starts at the cursor: `'...'`, `"..."` or a raw `r#'...'#`.
+    fn at_literal(&self) -> bool {
+        let rest = &self.input[self.cursor..];
+        match rest.strip_prefix('r') {
+            Some(raw) => raw.trim_start_matches('#').starts_with('\''),
+            None => rest.starts_with(['\'', '"']),
         }
     }
 
     fn parse_literal(&mut self) -> Result<String, InvalidFormat> {
-        self.expect_char('\'')?;
+        if self.match_char('r') {
+            let rest = &self.input[self.cursor..];
+            let hashes = rest.len() - rest.trim_start_matches('#').len();
+            self.cursor += hashes;
+            self.expect_char('\'')?;
 
-        let mut escaping = false;
+            // the literal ends at the first quote followed by the same number of hashes
+            let mut terminator = String::from("'");
+            terminator.extend(core::iter::repeat_n('#', hashes));
+            let rest = &self.input[self.cursor..];
+            let line = if self.multiline {
+                rest
+            } else {
+                rest.split('\n').next().unwrap_or(rest)
+            };
+            let Some(len) = line.find(terminator.as_str()) else {
+                self.cursor += line.len();
+                return Err(InvalidFormat);
+            };
+            self.cursor += len + terminator.len();
+            return Ok(rest[..len].into());
+        }
+
+        let quote = match self.peek_char() {
+            Some(quote @ ('\'' | '"')) => quote,
+            _ => return Err(InvalidFormat),
+        };
+        self.cursor += 1;
 
         let mut ret = String::with_capacity(32);
         loop {
             let c = self.input[self.cursor..].chars().next().ok_or(InvalidFormat)?;
+            if c == '\n' && !self.multiline {
+                return Err(InvalidFormat);
+            }
             self.cursor += c.len_utf8();
-            if escaping {
-                escaping = false;
-
-                match c {
-                    '\'' => ret.push('\''),
-                    'n' => ret.push('\n'),
-                    't' => ret.push('\t'),
-                    '\\' => ret.push('\\'),
-                    _ => ret.push(c),
-                }
-            } else {
-                if c == '\'' {
-                    break;
-                } else if c == '\\' {
-                    escaping = true;
-                } else {
+            match c {
+                c if c == quote => break,
+                '\\' => {
+                    let (c, len) = unescape_sequence(&self.input[self.cursor..], self.strict).ok_or(InvalidFormat)?;
+                    self.cursor += len;
                     ret.push(c);
                 }
+                c => ret.push(c),
             }
         }
 
         Ok(ret)
     }
 
-    fn parse_macro(&mut self) -> Result<(String, Vec<I18nString>), InvalidFormat> {
-        self.expect_str("t!(")?;
-        self.skip_whitespace();
-
-        let template = self.parse_literal()?;
-        let mut args: Vec<I18nString> = Vec::with_capacity(3);
-        loop {
+    /// Parse the named parameters and the template string of a template, e.g. `ctx = 'verb', 'Open'`.
+    fn parse_template_key(&mut self) -> Result<TemplateKey, InvalidFormat> {
+        let mut key = TemplateKey::default();
+        while self.peek_char().is_some_and(|c| c.is_ascii_lowercase()) && !self.at_literal() {
+            let rest = &self.input[self.cursor..];
+            let name = &rest[..rest.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(rest.len())];
+            self.cursor += name.len();
             self.skip_whitespace();
-            if self.match_char(')') {
-                break;
-            } else if self.match_char(',') {
-                self.skip_whitespace();
-                args.push(self.parse()?);
-            } else {
+            self.expect_char('=')?;
+            self.skip_whitespace();
+            let value = CompactString::from(self.parse_literal()?);
+
+            let duplicated = match name {
+                "ctx" => key.context.replace(value).is_some(),
+                "id" => key.id.replace(value).is_some(),
+                "note" => key.note.replace(value).is_some(),
+                _ => {
+                    let index = name
+                        .strip_prefix("note")
+                        .and_then(|n| usize::from_str(n).ok())
+                        .ok_or(InvalidFormat)?;
+                    let duplicated = key.placeholder_note(index).is_some();
+                    key.set_placeholder_note(index, Some(value));
+                    duplicated
+                }
+            };
+            if duplicated {
                 return Err(InvalidFormat);
             }
+
+            self.skip_whitespace();
+            self.expect_char(',')?;
+            self.skip_whitespace();
         }
 
-        Ok((template, args))
+        key.text = self.parse_literal()?.into();
+        Ok(key)
     }
 
-    fn parse(&mut self) -> Result<I18nString, InvalidFormat> {
-        if self.peek_char() == Some('\'') {
-            let text = self.parse_literal()?;
-            Ok(I18nString::Literal(text.into()))
-        } else if self.input[self.cursor..].starts_with("t!(") {
-            let (template, args) = self.parse_macro()?;
-            Ok(I18nString::Template(template.into(), args.into_boxed_slice()))
-        } else {
-            Err(InvalidFormat)
+    fn parse_number<T: FromStr>(&mut self) -> Result<T, InvalidFormat> {
+        let rest = &self.input[self.cursor..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '-' || c == '.'))
+            .unwrap_or(rest.len());
+        let number = T::from_str(&rest[..len]).map_err(|_| InvalidFormat)?;
+        self.cursor += len;
+        Ok(number)
+    }
+
+    fn parse_value(&mut self) -> Result<Value, InvalidFormat> {
+        let rest = &self.input[self.cursor..];
+        let name = &rest[..rest.find(|c: char| !c.is_ascii_lowercase()).unwrap_or(rest.len())];
+        self.cursor += name.len();
+        self.expect_str("!(")?;
+        self.skip_whitespace();
+
+        let value = match name {
+            "int" => Value::Integer(self.parse_number()?),
+            "dec" => Value::Decimal(self.parse_number()?),
+            "pct" => Value::Percent(self.parse_number()?),
+            "cur" => {
+                let amount: Decimal = self.parse_number()?;
+                self.skip_whitespace();
+                self.expect_char(',')?;
+                self.skip_whitespace();
+                Value::Currency(amount, self.parse_literal()?.into())
+            }
+            "date" => Value::Date(self.parse_number()?),
+            "dt" => Value::DateTime(self.parse_number()?),
+            "dur" => Value::Duration(self.parse_number()?),
+            _ => return Err(InvalidFormat),
+        };
+
+        self.skip_whitespace();
+        self.expect_char(')')?;
+        Ok(value)
+    }
+
+    /// Parse a string with an explicit stack of open templates instead of recursion.
+    fn parse(&mut self) -> Result<I18nString, ParseError> {
+        // open templates, or `None` for an open sensitive argument
+        let mut stack: Vec<(Option<TemplateKey>, Vec<I18nString>)> = Vec::new();
+
+        loop {
+            self.nodes += 1;
+            if self.nodes > self.limits.max_nodes {
+                return Err(LimitExceeded::Nodes.into());
+            }
+            if stack.len() >= self.limits.max_depth {
+                return Err(LimitExceeded::Depth.into());
+            }
+
+            let mut node = if self.at_literal() {
+                Some(I18nString::Literal(self.parse_literal()?.into()))
+            } else if self.match_str("t!(") {
+                self.skip_whitespace();
+                let template = self.parse_template_key()?;
+                stack.push((Some(template), Vec::with_capacity(3)));
+                None
+            } else if self.match_str("s!(") {
+                self.skip_whitespace();
+                stack.push((None, Vec::with_capacity(1)));
+                continue;
+            } else if self.peek_char().is_some_and(|c| c.is_ascii_lowercase()) {
+                Some(I18nString::Value(self.parse_value()?))
+            } else {
+                return Err(InvalidFormat.into());
+            };
+
+            // close finished templates until another argument follows
+            loop {
+                let Some((template, args)) = stack.last_mut() else {
+                    return node.ok_or(InvalidFormat.into());
+                };
+                args.extend(node.take());
+
+                self.skip_whitespace();
+                if self.match_char(')') {
+                    node = match stack.pop().ok_or(InvalidFormat)? {
+                        (Some(template), args) => Some(I18nString::Template(template, args.into_boxed_slice())),
+                        (None, mut args) if args.len() == 1 => args.pop().map(I18nString::sensitive),
+                        (None, _) => return Err(InvalidFormat.into()),
+                    };
+                } else if template.is_some() && self.match_char(',') {
+                    self.skip_whitespace();
+                    break;
+                } else {
+                    return Err(InvalidFormat.into());
+                }
+            }
         }
     }
 
-    fn parse_root(&mut self) -> Result<I18nString, InvalidFormat> {
+    fn parse_root(&mut self) -> Result<I18nString, ParseError> {
         let s = self.parse()?;
         self.skip_whitespace();
         if self.cursor >= self.input.len() {
             Ok(s)
         } else {
-            Err(InvalidFormat)
+            Err(InvalidFormat.into())
         }
     }
 }
 
-pub fn parse(input: &str) -> Result<I18nString, InvalidFormat> {
-    let mut parser = Parser::new(input);
+pub fn parse(input: &str, limits: &Limits, strict: bool) -> Result<I18nString, ParseError> {
+    let mut parser = Parser::new(input, limits, strict);
     parser.parse_root()
 }
+
+/// Parse a string at the start of `input`, returns the result and the number of bytes consumed, or the position of the error.
+///
+/// Literals may not contain line breaks, so an unterminated quote fails at the end of its line.
+pub fn parse_prefix(input: &str, limits: &Limits, strict: bool) -> (Result<I18nString, ParseError>, usize) {
+    let mut parser = Parser::new(input, limits, strict);
+    parser.multiline = false;
+    let result = parser.parse();
+    (result, parser.cursor)
+}
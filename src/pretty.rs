@@ -0,0 +1,81 @@
+use core::fmt::{Display, Formatter};
+
+use crate::{I18nString, format, redact::Redaction};
+
+/// Default line width of pretty formatting.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Default indentation of pretty formatting.
+pub const DEFAULT_INDENT: usize = 4;
+
+/// Layout of pretty formatting.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{I18nString, pretty::Layout};
+///
+/// let s = I18nString::template("{0} of {1}", [I18nString::value(3), I18nString::template("pages", [])]);
+/// assert_eq!(s.pretty(Layout::new().width(20).indent(2)).to_string(), "t!(\n  '{0} of {1}',\n  int!(3),\n  t!('pages')\n)");
+/// assert_eq!(s.pretty(Layout::new()).to_string(), s.to_string());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Layout {
+    pub(crate) width: usize,
+    pub(crate) indent: usize,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Layout {
+    /// Create a new `Layout` with the default width and indentation.
+    pub fn new() -> Self {
+        Self {
+            width: DEFAULT_WIDTH,
+            indent: DEFAULT_INDENT,
+        }
+    }
+
+    /// Set the line width, templates longer than it are broken over multiple lines.
+    ///
+    /// Parts that can not be broken, like long template texts, may still exceed it. A width of 0 breaks every template.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the number of spaces each nesting level is indented by.
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+}
+
+/// Display wrapper formatting an `I18nString` over multiple indented lines.
+///
+/// Created by [`I18nString::pretty`], the output is accepted by parsing like the compact form.
+#[derive(Debug, Copy, Clone)]
+pub struct Pretty<'a> {
+    pub(crate) s: &'a I18nString,
+    pub(crate) layout: Layout,
+    pub(crate) redaction: Option<Redaction>,
+}
+
+impl Pretty<'_> {
+    /// Redact sensitive arguments.
+    pub fn redact(mut self, redaction: Redaction) -> Self {
+        self.redaction = Some(redaction);
+        self
+    }
+}
+
+impl Display for Pretty<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        format::format_pretty_to(f, self.s, self.redaction, &self.layout)
+    }
+}
@@ -0,0 +1,131 @@
+use std::{borrow::Cow, collections::BTreeMap, sync::Mutex};
+
+use compact_str::CompactString;
+
+use crate::{
+    Resolver,
+    catalog::{Catalog, Message},
+};
+
+/// Statistics of a recorded template.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Record {
+    /// Number of times the template was resolved.
+    pub requests: u64,
+    /// Number of times the template had no translation.
+    pub misses: u64,
+}
+
+/// A resolver recording every template it resolves and which of them have no translation.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{I18nString, catalog::{Catalog, Message}, record::RecordingResolver};
+///
+/// let mut catalog = Catalog::new();
+/// catalog.insert(Message::new("Hello {0}", "Hallo {0}"));
+///
+/// let resolver = RecordingResolver::new(catalog);
+/// let s = I18nString::template("Hello {0}", [I18nString::template("World", [])]);
+/// assert_eq!(s.translate(&resolver), "Hallo World");
+///
+/// assert_eq!(resolver.missing(), ["World"]);
+/// assert_eq!(resolver.missing_catalog().to_po(), "msgid \"World\"\nmsgstr \"\"\n");
+/// ```
+#[derive(Debug, Default)]
+pub struct RecordingResolver<R> {
+    inner: R,
+    records: Mutex<BTreeMap<CompactString, Record>>,
+}
+
+impl<R> RecordingResolver<R> {
+    /// Create a new `RecordingResolver` wrapping a resolver.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            records: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Get the wrapped resolver.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get the wrapped resolver, discarding the records.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Get a snapshot of all records, keyed by template.
+    ///
+    /// Templates with a context are keyed by the context and the template joined with `\x04` like gettext does.
+    pub fn records(&self) -> BTreeMap<CompactString, Record> {
+        self.records.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Get all templates that missed a translation at least once, in order.
+    pub fn missing(&self) -> Vec<CompactString> {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records
+            .iter()
+            .filter(|(_, record)| record.misses > 0)
+            .map(|(template, _)| template.clone())
+            .collect()
+    }
+
+    /// Get a catalog template with untranslated messages for all missing templates.
+    pub fn missing_catalog(&self) -> Catalog {
+        let mut catalog = Catalog::new();
+        catalog.extend(self.missing().into_iter().map(|key| match key.split_once('\x04') {
+            Some((context, template)) => Message {
+                context: Some(context.into()),
+                ..Message::new(template, "")
+            },
+            None => Message::new(key, ""),
+        }));
+        catalog
+    }
+
+    /// Clear all records.
+    pub fn clear(&self) {
+        self.records.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+impl<R: Resolver> Resolver for RecordingResolver<R> {
+    fn resolve<'s>(&'s self, template: &'s str) -> Cow<'s, str> {
+        self.lookup(template).unwrap_or(Cow::Borrowed(template))
+    }
+
+    fn lookup<'s>(&'s self, template: &'s str) -> Option<Cow<'s, str>> {
+        self.lookup_in_context(None, template)
+    }
+
+    fn resolve_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Cow<'s, str> {
+        self.lookup_in_context(context, template).unwrap_or(Cow::Borrowed(template))
+    }
+
+    fn lookup_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Option<Cow<'s, str>> {
+        let resolved = self.inner.lookup_in_context(context, template);
+
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        let record = match context {
+            Some(context) => records
+                .entry(compact_str::format_compact!("{context}\x04{template}"))
+                .or_default(),
+            None => match records.get_mut(template) {
+                Some(record) => record,
+                None => records.entry(template.into()).or_default(),
+            },
+        };
+        record.requests += 1;
+        if resolved.is_none() {
+            record.misses += 1;
+        }
+
+        resolved
+    }
+}
@@ -0,0 +1,60 @@
+use core::fmt::{Display, Formatter, Write};
+
+use crate::{I18nString, format, pretty::Layout};
+
+/// Replacement of sensitive arguments.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum Redaction {
+    /// Replace with `[redacted]`.
+    Mask,
+    /// Replace with `[redacted:<hash>]`, a 64-bit FNV-1a hash of the argument.
+    ///
+    /// Equal arguments have equal hashes, so log entries can be correlated without showing the value.
+    /// The hash is not salted, low entropy values like numbers can be recovered by brute force.
+    Hash,
+}
+
+/// FNV-1a hasher of formatted text.
+struct Fnv(u64);
+
+impl Write for Fnv {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for b in s.bytes() {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+        Ok(())
+    }
+}
+
+/// Write the redaction of a sensitive argument.
+pub(crate) fn write_redacted<W: Write>(output: &mut W, inner: &I18nString, redaction: Redaction) -> core::fmt::Result {
+    match redaction {
+        Redaction::Mask => output.write_str("[redacted]"),
+        Redaction::Hash => {
+            // hash the serialized argument, so the hash does not depend on the locale or resolver
+            let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+            format::format_to(&mut hasher, inner, None)?;
+            write!(output, "[redacted:{:016x}]", hasher.0)
+        }
+    }
+}
+
+/// Display wrapper formatting an `I18nString` with its sensitive arguments redacted.
+///
+/// Created by [`I18nString::redacted`], `{:#}` formats it over multiple lines like the string does.
+#[derive(Debug, Copy, Clone)]
+pub struct Redacted<'a> {
+    pub(crate) s: &'a I18nString,
+    pub(crate) redaction: Redaction,
+}
+
+impl Display for Redacted<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            format::format_pretty_to(f, self.s, Some(self.redaction), &Layout::new())
+        } else {
+            format::format_to(f, self.s, Some(self.redaction))
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    Resolver,
+    catalog::Catalog,
+    locale::{Locale, negotiate},
+};
+
+/// A resolver shared between threads.
+pub type SharedResolver = Arc<dyn Resolver + Send + Sync>;
+
+/// A loaded resolver, or the error of loading its catalog.
+type Loaded = Result<SharedResolver, Arc<io::Error>>;
+
+/// Name of the catalog file in each locale directory.
+pub const CATALOG_FILE_NAME: &str = "messages.po";
+
+/// A thread-safe registry of resolvers keyed by locale.
+///
+/// Resolvers can be registered directly or loaded lazily from a directory with a
+/// `<dir>/<tag>/messages.po` layout. Loaded catalogs are kept for the lifetime of the registry,
+/// and so are failures, a catalog that fails to load is not read again.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{I18nString, catalog::{Catalog, Message}, locale::parse_accept_language, registry::ResolverRegistry};
+///
+/// let mut de = Catalog::new();
+/// de.insert(Message::new("Hello {0}", "Hallo {0}"));
+///
+/// let mut registry = ResolverRegistry::new();
+/// registry.insert("de".parse().unwrap(), de);
+/// registry.set_default("en".parse().unwrap());
+///
+/// let s = I18nString::template("Hello {0}", [I18nString::literal("Bob")]);
+/// let resolver = registry.resolver(&parse_accept_language("de-AT, en;q=0.5")).unwrap();
+/// assert_eq!(resolver.locale().unwrap().to_string(), "de");
+/// assert_eq!(s.translate(&resolver), "Hallo Bob");
+/// ```
+#[derive(Default)]
+pub struct ResolverRegistry {
+    files: HashMap<Locale, PathBuf>,
+    available: Vec<Locale>,
+    default: Option<Locale>,
+    resolvers: RwLock<HashMap<Locale, Loaded>>,
+}
+
+impl ResolverRegistry {
+    /// Create a new empty `ResolverRegistry`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a `ResolverRegistry` loading catalogs lazily from `<dir>/<tag>/messages.po`.
+    ///
+    /// Directory names are parsed leniently, so gettext style `pt_BR` or lowercase `pt-br` directories are found
+    /// as `pt-BR`, the first one in name order wins if several name the same locale.
+    /// Sub directories that are not valid language tags or have no catalog file are ignored.
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let mut entries = std::fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let mut files = HashMap::new();
+        for entry in entries {
+            let file = entry.path().join(CATALOG_FILE_NAME);
+            if !file.is_file() {
+                continue;
+            }
+            if let Some(locale) = entry.file_name().to_str().and_then(|name| name.parse::<Locale>().ok()) {
+                files.entry(locale).or_insert(file);
+            }
+        }
+        let mut available: Vec<Locale> = files.keys().cloned().collect();
+        available.sort();
+
+        Ok(Self {
+            files,
+            available,
+            ..Self::default()
+        })
+    }
+
+    /// Register a resolver for a locale.
+    pub fn insert<R: Resolver + Send + Sync + 'static>(&mut self, locale: Locale, resolver: R) {
+        if !self.available.contains(&locale) {
+            self.available.push(locale.clone());
+        }
+        self.resolvers
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(locale, Ok(Arc::new(resolver)));
+    }
+
+    /// Set the locale used when negotiation fails, it is also the last fallback of every resolver.
+    pub fn set_default(&mut self, locale: Locale) {
+        self.default = Some(locale);
+    }
+
+    /// Get all available locales.
+    pub fn available(&self) -> &[Locale] {
+        &self.available
+    }
+
+    fn load(&self, locale: &Locale) -> Loaded {
+        if let Some(loaded) = self.resolvers.read().unwrap_or_else(|e| e.into_inner()).get(locale) {
+            return loaded.clone();
+        }
+
+        let file = self
+            .files
+            .get(locale)
+            .ok_or_else(|| Arc::new(io::ErrorKind::NotFound.into()))?;
+        let loaded = match Catalog::load_po(file) {
+            Ok(catalog) => Ok(Arc::new(catalog) as SharedResolver),
+            Err(e) => Err(Arc::new(e)),
+        };
+
+        let mut resolvers = self.resolvers.write().unwrap_or_else(|e| e.into_inner());
+        resolvers.entry(locale.clone()).or_insert(loaded).clone()
+    }
+
+    /// Get a resolver for the best available locale of the requested ones.
+    ///
+    /// The resolver falls back along the parent chain of the negotiated locale, then to the default locale.
+    /// Templates without any translation resolve to themselves.
+    ///
+    /// Catalogs that fail to load are skipped, it is only an error if no catalog of the chain could be loaded.
+    pub fn resolver(&self, requested: &[Locale]) -> io::Result<LocaleResolver> {
+        let locale = negotiate(requested, &self.available).or(self.default.as_ref()).cloned();
+
+        let mut chain: Vec<&Locale> = Vec::new();
+        if let Some(locale) = &locale {
+            for candidate in locale.fallback_chain() {
+                if let Some(found) = self.available.iter().find(|a| **a == candidate) {
+                    chain.push(found);
+                }
+            }
+        }
+        if let Some(default) = &self.default
+            && self.available.contains(default)
+            && !chain.contains(&default)
+        {
+            chain.push(default);
+        }
+
+        let mut resolvers = Vec::with_capacity(chain.len());
+        let mut error = None;
+        for locale in chain {
+            match self.load(locale) {
+                Ok(resolver) => resolvers.push(resolver),
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+        if resolvers.is_empty()
+            && let Some(e) = error
+        {
+            return Err(io::Error::new(e.kind(), e));
+        }
+
+        Ok(LocaleResolver {
+            locale,
+            chain: resolvers,
+        })
+    }
+}
+
+/// A resolver for a negotiated locale, looking up templates along its fallback chain.
+#[derive(Clone)]
+pub struct LocaleResolver {
+    locale: Option<Locale>,
+    chain: Vec<SharedResolver>,
+}
+
+impl LocaleResolver {
+    /// Get the negotiated locale, `None` if no locale matched and there is no default.
+    pub fn locale(&self) -> Option<&Locale> {
+        self.locale.as_ref()
+    }
+}
+
+impl Resolver for LocaleResolver {
+    fn resolve<'s>(&'s self, template: &'s str) -> Cow<'s, str> {
+        self.lookup(template).unwrap_or(Cow::Borrowed(template))
+    }
+
+    fn lookup<'s>(&'s self, template: &'s str) -> Option<Cow<'s, str>> {
+        self.chain.iter().find_map(|resolver| resolver.lookup(template))
+    }
+
+    fn resolve_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Cow<'s, str> {
+        self.lookup_in_context(context, template).unwrap_or(Cow::Borrowed(template))
+    }
+
+    fn lookup_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Option<Cow<'s, str>> {
+        self.chain
+            .iter()
+            .find_map(|resolver| resolver.lookup_in_context(context, template))
+    }
+}
@@ -0,0 +1,261 @@
+use core::ops::Range;
+
+use crate::{
+    I18nString, ParseError,
+    limit::{LimitExceeded, Limits},
+    parse,
+};
+
+/// Result of parsing the next string of a buffer.
+enum Step {
+    Parsed(Range<usize>, Result<I18nString, ParseError>),
+    /// The input ended inside a string, more input may complete it.
+    Incomplete,
+    /// Only whitespace is left.
+    End,
+}
+
+/// Parse the next string of `input` after `position` and advance `position` past it.
+///
+/// An invalid string is skipped to the end of the line where it started, so parsing resumes with the next line.
+/// Unless the input ended, a string that is unfinished after `max_lines` lines is invalid.
+fn next(input: &str, position: &mut usize, limits: &Limits, strict: bool, eof: bool, max_lines: usize) -> Step {
+    let rest = &input[*position..];
+    let start = *position + rest.len() - rest.trim_start().len();
+    if start == input.len() {
+        *position = start;
+        return Step::End;
+    }
+
+    let (result, len) = parse::parse_prefix(&input[start..], limits, strict);
+    let end = start + len;
+    let error = match result {
+        Ok(s) => {
+            *position = end;
+            return Step::Parsed(start..end, Ok(s));
+        }
+        Err(ParseError::InvalidFormat) if !eof && input[end..].trim_start().is_empty() => {
+            if input[start..].lines().count() < max_lines {
+                return Step::Incomplete;
+            }
+            LimitExceeded::Lines.into()
+        }
+        Err(e) => e,
+    };
+    let line_end = input[start..].find('\n').map_or(input.len(), |n| start + n);
+    *position = line_end;
+    Step::Parsed(start..start + input[start..line_end].trim_end().len(), Err(error))
+}
+
+/// Iterator over the strings in a text, e.g. a log with one or more serialized strings per line.
+///
+/// Strings may be separated by whitespace or directly follow each other. Every string is returned with its
+/// byte range in the text. After an invalid string, parsing resumes with the line after the one where it
+/// started, and the range of the error covers the skipped text.
+///
+/// Literals may not contain line breaks, serialized strings always escape them.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{I18nString, ParseError, stream::Messages};
+///
+/// let log = "t!('Saved {0}', 'a.txt') 'done'\nerror: disk full\nt!('Bye')\n";
+/// let messages: Vec<_> = Messages::new(log).collect();
+/// assert_eq!(
+///     messages,
+///     [
+///         (0..24, Ok(I18nString::template("Saved {0}", [I18nString::literal("a.txt")]))),
+///         (25..31, Ok(I18nString::literal("done"))),
+///         (32..48, Err(ParseError::InvalidFormat)),
+///         (49..58, Ok(I18nString::template("Bye", []))),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Messages<'s> {
+    input: &'s str,
+    position: usize,
+    limits: Limits,
+    strict: bool,
+}
+
+impl<'s> Messages<'s> {
+    /// Create an iterator over the strings in `input`.
+    pub fn new(input: &'s str) -> Self {
+        Self {
+            input,
+            position: 0,
+            limits: Limits::default(),
+            strict: false,
+        }
+    }
+
+    /// Limit every string, like [`I18nString::parse_with_limits`].
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Reject unknown escape sequences, like [`I18nString::parse_strict`].
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+}
+
+impl Iterator for Messages<'_> {
+    type Item = (Range<usize>, Result<I18nString, ParseError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match next(self.input, &mut self.position, &self.limits, self.strict, true, usize::MAX) {
+            Step::Parsed(span, result) => Some((span, result)),
+            Step::Incomplete | Step::End => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub use reader::MessageReader;
+
+#[cfg(feature = "std")]
+mod reader {
+    use std::{
+        io::{self, BufRead},
+        ops::Range,
+    };
+
+    use super::{Step, next};
+    use crate::{I18nString, ParseError, limit::Limits};
+
+    /// Default maximum number of lines of a string read by a [`MessageReader`].
+    const DEFAULT_MAX_LINES: usize = 1024;
+
+    /// Iterator over the strings read from a [`BufRead`], like [`Messages`](super::Messages) for a text.
+    ///
+    /// The input is read line by line, and only the lines of the current string are kept in memory. A string
+    /// may span at most [`max_lines`](Self::max_lines) lines, longer strings are skipped with a
+    /// [`LimitExceeded::Lines`](crate::limit::LimitExceeded::Lines) error. Ranges are byte offsets from the start of the input.
+    ///
+    /// A line that is not valid UTF-8 is skipped with an [`io::ErrorKind::InvalidData`] error, together with
+    /// an unfinished string before it. Iteration may continue after any error.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// use i18n_string::{I18nString, stream::MessageReader};
+    ///
+    /// let log = Cursor::new("t!(\n    'Hello {0}',\n    'world'\n)\ninvalid\n'next'");
+    /// let messages: Vec<_> = MessageReader::new(log).map(Result::unwrap).collect();
+    /// assert_eq!(messages[0].0, 0..34);
+    /// assert_eq!(messages[0].1, Ok(I18nString::template("Hello {0}", [I18nString::literal("world")])));
+    /// assert!(messages[1].1.is_err());
+    /// assert_eq!(messages[2], (43..49, Ok(I18nString::literal("next"))));
+    /// ```
+    #[derive(Debug)]
+    pub struct MessageReader<R> {
+        reader: R,
+        buffer: String,
+        position: usize,
+        offset: usize,
+        eof: bool,
+        limits: Limits,
+        strict: bool,
+        max_lines: usize,
+    }
+
+    impl<R: BufRead> MessageReader<R> {
+        /// Create an iterator over the strings read from `reader`.
+        pub fn new(reader: R) -> Self {
+            Self {
+                reader,
+                buffer: String::new(),
+                position: 0,
+                offset: 0,
+                eof: false,
+                limits: Limits::default(),
+                strict: false,
+                max_lines: DEFAULT_MAX_LINES,
+            }
+        }
+
+        /// Limit every string, like [`I18nString::parse_with_limits`].
+        pub fn limits(mut self, limits: Limits) -> Self {
+            self.limits = limits;
+            self
+        }
+
+        /// Reject unknown escape sequences, like [`I18nString::parse_strict`].
+        pub fn strict(mut self) -> Self {
+            self.strict = true;
+            self
+        }
+
+        /// Limit the number of lines of a string, 1024 by default.
+        ///
+        /// An unfinished string is parsed again for every line appended to it, the limit bounds this work and
+        /// the memory kept for the string.
+        pub fn max_lines(mut self, max_lines: usize) -> Self {
+            self.max_lines = max_lines;
+            self
+        }
+
+        /// Get the underlying reader.
+        pub fn into_inner(self) -> R {
+            self.reader
+        }
+
+        /// Drop the parsed text and append the next line, returns whether the input ended.
+        fn read_line(&mut self) -> io::Result<bool> {
+            self.buffer.drain(..self.position);
+            self.offset += self.position;
+            self.position = 0;
+
+            let mut line = Vec::new();
+            if self.reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(true);
+            }
+            match String::from_utf8(line) {
+                Ok(line) => {
+                    self.buffer.push_str(&line);
+                    Ok(false)
+                }
+                Err(e) => {
+                    self.offset += self.buffer.len() + e.as_bytes().len();
+                    self.buffer.clear();
+                    Err(io::Error::new(io::ErrorKind::InvalidData, e.utf8_error()))
+                }
+            }
+        }
+    }
+
+    impl<R: BufRead> Iterator for MessageReader<R> {
+        type Item = io::Result<(Range<usize>, Result<I18nString, ParseError>)>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                match next(
+                    &self.buffer,
+                    &mut self.position,
+                    &self.limits,
+                    self.strict,
+                    self.eof,
+                    self.max_lines,
+                ) {
+                    Step::Parsed(span, result) => {
+                        return Some(Ok((self.offset + span.start..self.offset + span.end, result)));
+                    }
+                    Step::End if self.eof => return None,
+                    Step::Incomplete | Step::End => match self.read_line() {
+                        Ok(eof) => self.eof = eof,
+                        Err(e) => return Some(Err(e)),
+                    },
+                }
+            }
+        }
+    }
+}
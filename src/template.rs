@@ -0,0 +1,227 @@
+use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
+use core::{ops::Range, str::FromStr};
+
+use crate::{TemplateKey, locale::Locale};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '<' => Some(Align::Left),
+            '^' => Some(Align::Center),
+            '>' => Some(Align::Right),
+            _ => None,
+        }
+    }
+}
+
+/// Largest width or precision of a spec, like `std::fmt`, larger ones make the placeholder invalid.
+const MAX_SPEC_VALUE: usize = u16::MAX as usize;
+
+/// Format spec of a placeholder, `[[fill]align][width][.precision]` like `std::fmt`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct FormatSpec {
+    pub(crate) fill: char,
+    pub(crate) align: Option<Align>,
+    pub(crate) width: Option<usize>,
+    pub(crate) precision: Option<usize>,
+}
+impl FromStr for FormatSpec {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut spec = FormatSpec {
+            fill: ' ',
+            align: None,
+            width: None,
+            precision: None,
+        };
+
+        let mut chars = s.chars();
+        let mut rest = s;
+        if let Some(first) = chars.next() {
+            if let Some(align) = chars.next().and_then(Align::from_char) {
+                spec.fill = first;
+                spec.align = Some(align);
+                rest = chars.as_str();
+            } else if let Some(align) = Align::from_char(first) {
+                spec.align = Some(align);
+                rest = &s[first.len_utf8()..];
+            }
+        }
+
+        let (width, precision) = match rest.split_once('.') {
+            Some((width, precision)) => (width, Some(precision)),
+            None => (rest, None),
+        };
+        let parse = |s: &str| usize::from_str(s).ok().filter(|&n| n <= MAX_SPEC_VALUE).ok_or(());
+        if !width.is_empty() {
+            spec.width = Some(parse(width)?);
+        }
+        if let Some(precision) = precision {
+            spec.precision = Some(parse(precision)?);
+        }
+
+        Ok(spec)
+    }
+}
+
+impl FormatSpec {
+    /// Apply the spec to the text written since `start`.
+    ///
+    /// Numeric text is right aligned by default and its precision is applied while formatting instead of here.
+    pub(crate) fn apply(&self, output: &mut String, start: usize, numeric: bool) {
+        if !numeric
+            && let Some(precision) = self.precision
+            && let Some((end, _)) = output[start..].char_indices().nth(precision)
+        {
+            output.truncate(start + end);
+        }
+
+        if let Some(width) = self.width {
+            let len = output[start..].chars().count();
+            if len < width {
+                let padding = width - len;
+                let default = if numeric { Align::Right } else { Align::Left };
+                let (left, right) = match self.align.unwrap_or(default) {
+                    Align::Left => (0, padding),
+                    Align::Center => (padding / 2, padding - padding / 2),
+                    Align::Right => (padding, 0),
+                };
+                let fill: String = core::iter::repeat_n(self.fill, left).collect();
+                output.insert_str(start, &fill);
+                output.extend(core::iter::repeat_n(self.fill, right));
+            }
+        }
+    }
+}
+
+fn parse_placeholder(placeholder: &str) -> Option<(usize, Option<FormatSpec>)> {
+    match placeholder.split_once(':') {
+        Some((index, spec)) => Some((usize::from_str(index).ok()?, Some(FormatSpec::from_str(spec).ok()?))),
+        None => Some((usize::from_str(placeholder).ok()?, None)),
+    }
+}
+
+/// A segment of a template, ranges point into the template source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Segment {
+    /// Text copied to the output as is.
+    Text(Range<usize>),
+    /// Text with invalid placeholders or unpaired braces, also copied to the output as is.
+    Invalid(Range<usize>),
+    /// An argument slot, `raw` is the whole placeholder written back when the argument is missing.
+    Argument {
+        index: usize,
+        spec: Option<FormatSpec>,
+        raw: Range<usize>,
+    },
+}
+
+/// Get the segment of a template at `pos` and advance `pos` past it, `None` at the end.
+///
+/// `{{` and `}}` are escaped braces, invalid placeholders and unpaired braces are kept as text.
+pub(crate) fn next_segment(template: &str, pos: &mut usize) -> Option<Segment> {
+    let start = *pos;
+    let rest = &template[start..];
+    let end = template.len();
+
+    match rest.find(['{', '}']) {
+        None if rest.is_empty() => None,
+        None => {
+            *pos = end;
+            Some(Segment::Text(start..end))
+        }
+        Some(0) => {
+            let after = &rest[1..];
+            if rest.starts_with('{') {
+                match after.find(['{', '}']) {
+                    Some(n) if after[n..].starts_with('}') => {
+                        *pos = start + n + 2;
+                        let raw = start..*pos;
+                        Some(match parse_placeholder(&after[..n]) {
+                            Some((index, spec)) => Segment::Argument { index, spec, raw },
+                            // ignore invalid format
+                            None => Segment::Invalid(raw),
+                        })
+                    }
+                    Some(0) => {
+                        // escaped left brace
+                        *pos = start + 2;
+                        Some(Segment::Text(start..start + 1))
+                    }
+                    Some(n) => {
+                        // unclosed left brace, text up to the next left brace is dropped
+                        *pos = start + n + 2;
+                        Some(Segment::Invalid(start..start + 1))
+                    }
+                    None => {
+                        // ignore unclosed left brace
+                        *pos = end;
+                        Some(Segment::Invalid(start..end))
+                    }
+                }
+            } else {
+                match after.chars().next() {
+                    Some('}') => {
+                        *pos = start + 2;
+                        Some(Segment::Text(start..start + 1))
+                    }
+                    Some(c) => {
+                        // ignore invalid format
+                        *pos = start + 1 + c.len_utf8();
+                        Some(Segment::Invalid(start..*pos))
+                    }
+                    None => {
+                        // ignore unclosed right brace
+                        *pos = end;
+                        Some(Segment::Invalid(start..end))
+                    }
+                }
+            }
+        }
+        Some(n) => {
+            *pos = start + n;
+            Some(Segment::Text(start..*pos))
+        }
+    }
+}
+
+/// A resolved template split into segments once, so it can be rendered without scanning it again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CompiledTemplate {
+    pub(crate) source: Box<str>,
+    pub(crate) segments: Box<[Segment]>,
+}
+
+impl CompiledTemplate {
+    pub(crate) fn new(source: &str) -> Self {
+        Self {
+            source: source.into(),
+            segments: segments(source).collect::<Vec<_>>().into(),
+        }
+    }
+}
+
+/// Iterate the segments of a template.
+pub(crate) fn segments(template: &str) -> impl Iterator<Item = Segment> + '_ {
+    let mut pos = 0;
+    core::iter::from_fn(move || next_segment(template, &mut pos))
+}
+
+/// Source of compiled templates, used by `translate_to` to skip scanning resolved templates.
+pub(crate) trait TemplateStore {
+    /// Get the compiled template for a template key and locale, compiling the resolved template on a miss.
+    fn get_or_compile(
+        &self,
+        locale: Option<&Locale>,
+        template: &TemplateKey,
+        resolve: &mut dyn FnMut() -> CompiledTemplate,
+    ) -> Arc<CompiledTemplate>;
+}
@@ -1,7 +1,16 @@
-use alloc::{borrow::Cow, format, string::ToString};
+use alloc::{borrow::Cow, string::ToString};
 use core::str::FromStr;
 
-use crate::{I18nString, Resolver};
+use crate::{
+    I18nString, NoResolver, ParseError, Resolver, TemplateKey, TranslateOptions,
+    bidi::{self, Direction},
+    catalog::{Catalog, Message},
+    escape::Context,
+    limit::{LimitExceeded, Limits},
+    locale::{Locale, negotiate, parse_accept_language},
+    number::{NumberFormat, NumberingSystem},
+    value::{Decimal, Value},
+};
 
 #[test]
 fn test_parse() {
@@ -126,7 +135,7 @@ fn test_format_and_parse() {
 
     for case in cases {
         let formatted = case.to_string();
-        let parsed = I18nString::from_str(&formatted).expect(&format!("Failed to parse formatted string: {formatted}"));
+        let parsed = I18nString::from_str(&formatted).unwrap_or_else(|_| panic!("Failed to parse formatted string: {formatted}"));
         assert_eq!(parsed, case);
     }
 }
@@ -137,7 +146,7 @@ fn test_translate() {
 
     impl Resolver for SimpleResolver {
         fn resolve<'s>(&'s self, fmt: &'s str) -> Cow<'s, str> {
-            match fmt.as_ref() {
+            match fmt {
                 "resource changed" => "资源变更".into(),
                 "io error: {0}" => "IO 错误: {0}".into(),
                 _ => fmt.into(),
@@ -174,3 +183,1508 @@ fn test_no_remaining() {
         assert_eq!(is_err, expected, "input: {input}");
     }
 }
+
+#[test]
+fn test_bidi_isolation() {
+    let s = I18nString::template(
+        "{0}: {1} ({2})",
+        [
+            I18nString::literal("تقرير"),
+            I18nString::literal("report.pdf"),
+            I18nString::literal("42"),
+        ],
+    );
+
+    let cases = [
+        (Direction::Rtl, "تقرير: \u{2066}report.pdf\u{2069} (\u{2068}42\u{2069})"),
+        (Direction::Ltr, "\u{2067}تقرير\u{2069}: report.pdf (\u{2068}42\u{2069})"),
+    ];
+    for (target, expected) in cases {
+        let output = s.translate_with(NoResolver, &TranslateOptions::new().isolate(target));
+        assert_eq!(output, expected);
+        assert_eq!(bidi::direction(&output), Some(target));
+    }
+
+    assert_eq!(s.translate(NoResolver), "تقرير: report.pdf (42)");
+    assert_eq!(bidi::direction("\u{2068}שלום\u{2069} world"), Some(Direction::Ltr));
+}
+
+#[test]
+fn test_escape_literals() {
+    let s = I18nString::template(
+        "<a href=\"{1}\">{0}</a> *{2}*",
+        [
+            I18nString::literal("Tom & <Jerry>"),
+            I18nString::literal("\"onclick='x'"),
+            I18nString::template("_{0}_", [I18nString::literal("[link](x)")]),
+        ],
+    );
+
+    let cases = [
+        (
+            Context::Html,
+            "<a href=\"&quot;onclick=&#39;x&#39;\">Tom &amp; &lt;Jerry&gt;</a> *_[link](x)_*",
+        ),
+        (
+            Context::Markdown,
+            "<a href=\"\"onclick='x'\">Tom \\& \\<Jerry\\></a> *_\\[link\\]\\(x\\)_*",
+        ),
+    ];
+    for (context, expected) in cases {
+        let output = s.translate_with(NoResolver, &TranslateOptions::new().escape_literals(context));
+        assert_eq!(output, expected);
+    }
+
+    let cases = [
+        ("\x1b[1;31merror\x1b[0m: bad", "error: bad"),
+        ("\x1b]0;title\x07text", "text"),
+        ("\x1b]8;;http://x\x1b\\link\x1b]8;;\x1b\\", "link"),
+        ("line\r\nnext\x08\tend", "line\nnext\tend"),
+        ("plain", "plain"),
+    ];
+    for (input, expected) in cases {
+        let output =
+            I18nString::literal(input).translate_with(NoResolver, &TranslateOptions::new().escape_literals(Context::Terminal));
+        assert_eq!(output, expected, "input: {input:?}");
+    }
+}
+
+#[test]
+fn test_format_spec() {
+    let args = [I18nString::literal("abc"), I18nString::literal("名前")];
+    let cases = [
+        ("[{0:5}]", "[abc  ]"),
+        ("[{0:>5}]", "[  abc]"),
+        ("[{0:^6}]", "[ abc  ]"),
+        ("[{0:*^7}]", "[**abc**]"),
+        ("[{0:.2}]", "[ab]"),
+        ("[{0:>4.1}]", "[   a]"),
+        ("[{0:2}]", "[abc]"),
+        ("[{0:}]", "[abc]"),
+        ("[{1:-<4}]", "[名前--]"),
+        ("[{1:.1}]", "[名]"),
+        ("[{0:x}]", "[{0:x}]"),
+        ("[{0:>}]", "[abc]"),
+        ("[{0:.}]", "[{0:.}]"),
+        ("[{2:5}]", "[{2:5}]"),
+    ];
+
+    for (template, expected) in cases {
+        let output = I18nString::template(template, args.clone()).translate(NoResolver);
+        assert_eq!(output, expected, "template: {template}");
+    }
+}
+
+#[test]
+fn test_typed_values() {
+    let cases = [
+        ("int!(1234567)", "en", "1,234,567"),
+        ("int!(1234567)", "de", "1.234.567"),
+        ("int!(1234567)", "fr-CA", "1\u{202f}234\u{202f}567"),
+        ("int!(-42)", "und", "-42"),
+        ("dec!(-1234.5)", "en-US", "-1,234.5"),
+        ("dec!(-1234.5)", "de-AT", "-1.234,5"),
+        ("pct!(0.256)", "en", "25.6%"),
+        ("pct!(0.256)", "de", "25,6\u{a0}%"),
+        ("pct!(1)", "tr", "%100"),
+        ("cur!(1234.5,'USD')", "en", "$1,234.50"),
+        ("cur!(1234.5, 'JPY')", "ja", "¥1,235"),
+        ("cur!(-0.001,'EUR')", "fr", "0,00\u{a0}€"),
+        ("cur!(99,'CHF')", "de", "99,00\u{a0}CHF"),
+        ("date!(1700000000)", "en", "11/14/2023"),
+        ("date!(1700000000)", "de", "14.11.2023"),
+        ("date!(-86400)", "xx", "1969-12-31"),
+        ("dt!(1700000000)", "en", "11/14/2023, 10:13:20\u{202f}PM"),
+        ("dt!(1700000000)", "ja-JP", "2023/11/14 22:13:20"),
+        ("dt!(1700000000)", "ko", "2023. 11. 14. 오후 10:13:20"),
+        ("dur!(93725)", "en", "26:02:05"),
+        ("t!('[{0:>8.2}]', dec!(3.14159))", "en", "[    3.14]"),
+        ("t!('[{0:<6.0}]', pct!(0.256))", "en", "[26%   ]"),
+        ("t!('[{0:.3}]', int!(7))", "de", "[7,000]"),
+    ];
+
+    for (input, locale, expected) in cases {
+        let s = I18nString::from_str(input).unwrap();
+        let output = s.translate_with(NoResolver, &TranslateOptions::new().locale(locale.parse().unwrap()));
+        assert_eq!(output, expected, "input: {input}, locale: {locale}");
+        assert_eq!(I18nString::from_str(&s.to_string()).unwrap(), s);
+    }
+
+    let s = I18nString::template(
+        "{0} {1}",
+        [
+            I18nString::value(Value::Percent(Decimal::new(5, 1))),
+            I18nString::value(Value::Duration(59)),
+        ],
+    );
+    assert_eq!(s.to_string(), "t!('{0} {1}',pct!(0.5),dur!(59))");
+    assert_eq!(s.translate(NoResolver), "50% 0:00:59");
+
+    for input in [
+        "int!(1.5)",
+        "dec!(1.)",
+        "dec!(.5)",
+        "cur!(1)",
+        "dur!(-1)",
+        "foo!(1)",
+        "int!(99999999999999999999)",
+    ] {
+        assert!(I18nString::from_str(input).is_err(), "input: {input}");
+    }
+}
+
+#[test]
+fn test_number_format() {
+    let cases = [
+        ("en", Decimal::new(-12345678, 2), "-123,456.78"),
+        ("en-IN", Decimal::new(123456789, 0), "12,34,56,789"),
+        ("hi", Decimal::new(1000, 0), "1,000"),
+        ("mr", Decimal::new(12345, 0), "१२,३४५"),
+        ("ne-NP", Decimal::new(1234567, 1), "१,२३,४५६.७"),
+        ("bn", Decimal::new(100000, 0), "১,০০,০০০"),
+        ("ar-EG", Decimal::new(12345, 1), "١٬٢٣٤٫٥"),
+        ("fa", Decimal::new(-1234, 0), "\u{200e}\u{2212}۱٬۲۳۴"),
+        ("es", Decimal::new(1234, 0), "1234"),
+        ("es", Decimal::new(12345, 0), "12.345"),
+        ("pl", Decimal::new(9999, 0), "9999"),
+        ("sv", Decimal::new(-5, 1), "\u{2212}0,5"),
+        ("xx", Decimal::new(1234, 3), "1.234"),
+    ];
+    for (locale, number, expected) in cases {
+        assert_eq!(
+            NumberFormat::for_locale(&locale.parse().unwrap()).format(number),
+            expected,
+            "locale: {locale}"
+        );
+    }
+
+    let thai =
+        NumberFormat::for_locale(&"th".parse().unwrap()).with_numbering_system(NumberingSystem::from_name("thai").unwrap());
+    assert_eq!(thai.format(2024), "๒,๐๒๔");
+
+    let s = I18nString::from_str("t!('{0} {1} {2}', pct!(0.5), date!(1700000000), dur!(61))").unwrap();
+    let output = s.translate_with(NoResolver, &TranslateOptions::new().locale("ar".parse().unwrap()));
+    assert_eq!(output, "٥٠٪\u{61c} ١٤\u{200f}/١١\u{200f}/٢٠٢٣ ٠:٠١:٠١");
+}
+
+#[test]
+fn test_locale() {
+    let cases = [
+        ("en", "en"),
+        ("EN_us", "en-US"),
+        ("iw-IL", "he-IL"),
+        ("zh-hant-tw", "zh-Hant-TW"),
+        ("es-419", "es-419"),
+        ("sl-rozaj-biske", "sl-rozaj-biske"),
+        ("de-DE-1996", "de-DE-1996"),
+        ("en-US-u-NU-Latn-x-Private", "en-US-u-nu-latn-x-private"),
+        ("root", "und"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(Locale::from_str(input).unwrap().to_string(), expected, "input: {input}");
+    }
+
+    for input in [
+        "",
+        "e",
+        "en-toolongvariant",
+        "en--US",
+        "en-u",
+        "en-US-u-nu-",
+        "en-a-b-c",
+        "12",
+    ] {
+        assert!(Locale::from_str(input).is_err(), "input: {input}");
+    }
+
+    let locale = Locale::from_str("ar-u-nu-latn").unwrap();
+    assert_eq!(locale.direction(), Direction::Rtl);
+    assert_eq!(Locale::from_str("az-Arab").unwrap().direction(), Direction::Rtl);
+    assert_eq!(Locale::from_str("ku-Latn").unwrap().direction(), Direction::Ltr);
+
+    let locales = |tags: &[&str]| {
+        tags.iter()
+            .map(|t| Locale::from_str(t).unwrap())
+            .collect::<alloc::vec::Vec<_>>()
+    };
+    let available = locales(&["en", "en-GB", "pt-BR", "sr-Cyrl", "zh-Hant"]);
+    let cases: [(&str, Option<&str>); 7] = [
+        ("en-GB-oxendict, en", Some("en-GB")),
+        ("en-AU", Some("en")),
+        ("pt-PT;q=0.9, en;q=0.1", Some("pt-BR")),
+        ("sr-Latn, zh-Hant-HK", Some("zh-Hant")),
+        ("sr", Some("sr-Cyrl")),
+        ("ja, ko", None),
+        ("", None),
+    ];
+    for (header, expected) in cases {
+        let requested = parse_accept_language(header);
+        let output = negotiate(&requested, &available).map(|l| l.to_string());
+        assert_eq!(output.as_deref(), expected, "header: {header}");
+    }
+
+    // a missing script is inferred, so traditional Chinese readers never get simplified Chinese
+    let cases: [(&[&str], &str, Option<&str>); 6] = [
+        (&["zh-Hans", "zh-Hant"], "zh-TW", Some("zh-Hant")),
+        (&["zh-Hans", "zh-Hant"], "zh-HK", Some("zh-Hant")),
+        (&["zh-Hans", "zh-Hant"], "zh, en", Some("zh-Hans")),
+        (&["zh", "en"], "zh-TW, en", Some("en")),
+        (&["zh", "en"], "zh-SG", Some("zh")),
+        (&["sr-Latn"], "sr", None),
+    ];
+    for (available, header, expected) in cases {
+        let output = negotiate(&parse_accept_language(header), &locales(available)).map(|l| l.to_string());
+        assert_eq!(output.as_deref(), expected, "header: {header}");
+    }
+
+    let s = I18nString::from_str("t!('{0}', int!(123456))").unwrap();
+    let cases = [
+        ("hi", "1,23,456"),
+        ("hi-u-nu-deva", "१,२३,४५६"),
+        ("ar-u-nu-latn", "123٬456"),
+        ("en-Latn-IN", "1,23,456"),
+    ];
+    for (locale, expected) in cases {
+        let output = s.translate_with(NoResolver, &TranslateOptions::new().locale(locale.parse().unwrap()));
+        assert_eq!(output, expected, "locale: {locale}");
+    }
+}
+
+#[test]
+fn test_catalog_po() {
+    let catalog = Catalog::parse_po(
+        r#"
+# translator comment
+msgid ""
+msgstr ""
+"Language: de\n"
+"Plural-Forms: nplurals=2; plural=(n != 1);\n"
+
+#: src/main.rs:10
+msgid "Open"
+msgstr "Öffnen"
+
+msgctxt "adjective"
+msgid "Open"
+msgstr "Offen"
+
+#, fuzzy
+msgid "Close"
+msgstr "Schließen"
+
+msgid ""
+"multi "
+"line {0}"
+msgstr ""
+"mehr\tzeilig "
+"{0}"
+
+msgid "{0} file"
+msgid_plural "{0} files"
+msgstr[0] "{0} Datei"
+msgstr[1] "{0} Dateien"
+
+msgid "untranslated"
+msgstr ""
+
+#~ msgid "obsolete"
+#~ msgstr "veraltet"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(catalog.header_field("language"), Some("de"));
+    assert_eq!(catalog.header_field("Plural-Forms"), Some("nplurals=2; plural=(n != 1);"));
+    assert_eq!(catalog.len(), 6);
+    assert_eq!(catalog.get(Some("adjective"), "Open").unwrap().translation(), Some("Offen"));
+    assert_eq!(
+        catalog.get(None, "{0} file").unwrap().translations,
+        ["{0} Datei", "{0} Dateien"]
+    );
+
+    let cases = [
+        ("Open", Some("Öffnen")),
+        ("Close", None),
+        ("multi line {0}", Some("mehr\tzeilig {0}")),
+        ("{0} file", Some("{0} Datei")),
+        ("untranslated", None),
+        ("obsolete", None),
+    ];
+    for (template, expected) in cases {
+        assert_eq!(catalog.lookup(template).as_deref(), expected, "template: {template}");
+    }
+    assert_eq!(I18nString::template("Close", []).translate(&catalog), "Close");
+
+    for input in [
+        "msgid \"a\"\nmsgstr \"b",
+        "\"orphan\"",
+        "msgid \"a\"\nmsgstr[x] \"b\"",
+        "msgid \"a\"\nmsgid_plural \"b\"\nmsgstr[6] \"c\"",
+        "msgid \"a\"\nmsgid_plural \"b\"\nmsgstr[18446744073709551615] \"c\"",
+        "msgfoo \"a\"",
+    ] {
+        assert!(Catalog::parse_po(input).is_err(), "input: {input}");
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_resolver_registry() {
+    use std::{fs, sync::Arc, thread};
+
+    use crate::registry::ResolverRegistry;
+
+    let dir = std::env::temp_dir().join(format!("i18n-string-registry-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    for (tag, content) in [
+        (
+            "en",
+            "msgid \"greeting\"\nmsgstr \"Hello {0}\"\nmsgid \"bye\"\nmsgstr \"Bye\"\n",
+        ),
+        (
+            "pt",
+            "msgid \"greeting\"\nmsgstr \"Olá {0}\"\nmsgid \"bye\"\nmsgstr \"Tchau\"\n",
+        ),
+        ("pt_BR", "msgid \"greeting\"\nmsgstr \"Oi {0}\"\n"),
+        ("fr", "msgid \"greeting\"\nmsgstr \"broken"),
+        ("templates", "msgid \"x\"\nmsgstr \"\"\n"),
+    ] {
+        fs::create_dir_all(dir.join(tag)).unwrap();
+        fs::write(dir.join(tag).join("messages.po"), content).unwrap();
+    }
+    fs::create_dir_all(dir.join("de")).unwrap();
+
+    let mut registry = ResolverRegistry::from_dir(&dir).unwrap();
+    registry.set_default("en".parse().unwrap());
+    let tags: alloc::vec::Vec<_> = registry.available().iter().map(|l| l.to_string()).collect();
+    assert_eq!(tags, ["en", "fr", "pt", "pt-BR"]);
+
+    let registry = Arc::new(registry);
+    let cases = [
+        ("pt-BR", "Oi Ana / Tchau"),
+        ("pt-PT", "Olá Ana / Tchau"),
+        ("de, en", "Hello Ana / Bye"),
+        ("ja", "Hello Ana / Bye"),
+        ("fr", "Hello Ana / Bye"),
+    ];
+    let handles: alloc::vec::Vec<_> = cases
+        .into_iter()
+        .map(|(header, expected)| {
+            let registry = registry.clone();
+            thread::spawn(move || {
+                let s = I18nString::from_str("t!('{0} / {1}', t!('greeting', 'Ana'), t!('bye'))").unwrap();
+                let resolver = registry.resolver(&parse_accept_language(header)).unwrap();
+                assert_eq!(s.translate(&resolver), expected, "header: {header}");
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // the broken catalog is skipped without reading it again
+    fs::write(dir.join("fr").join("messages.po"), "msgid \"bye\"\nmsgstr \"Salut\"\n").unwrap();
+    let resolver = registry.resolver(&parse_accept_language("fr")).unwrap();
+    assert_eq!(resolver.locale().unwrap().to_string(), "fr");
+    assert_eq!(resolver.resolve("bye"), "Bye");
+
+    let mut registry = ResolverRegistry::from_dir(&dir).unwrap();
+    fs::write(dir.join("fr").join("messages.po"), "msgstr").unwrap();
+    assert!(registry.resolver(&parse_accept_language("fr")).is_err());
+    registry.set_default("en".parse().unwrap());
+    assert!(registry.resolver(&parse_accept_language("fr")).is_ok());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_watched_catalog() {
+    use std::{fs, sync::Arc, thread, time::Duration};
+
+    use crate::watch::WatchedCatalog;
+
+    let dir = std::env::temp_dir().join(format!("i18n-string-watch-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let base = dir.join("base.po");
+    let extra = dir.join("extra.po");
+    fs::write(
+        &base,
+        "msgid \"hello\"\nmsgstr \"hallo\"\nmsgid \"bye\"\nmsgstr \"tschüss\"\n",
+    )
+    .unwrap();
+    fs::write(&extra, "msgid \"bye\"\nmsgstr \"ciao\"\n").unwrap();
+
+    let catalog = Arc::new(WatchedCatalog::open_all([&base, &extra]).unwrap());
+    let s = I18nString::from_str("t!('{0}, {1}', t!('hello'), t!('bye'))").unwrap();
+    assert_eq!(s.translate(&catalog), "hallo, ciao");
+    assert!(!catalog.reload_if_changed().unwrap());
+
+    fs::write(&base, "msgid \"hello\"\nmsgstr \"servus\"\n").unwrap();
+    assert!(catalog.reload_if_changed().unwrap());
+    assert_eq!(s.translate(&catalog), "servus, ciao");
+
+    // a rewrite of the same size is detected by the content hash
+    fs::write(&base, "msgid \"hello\"\nmsgstr \"servas\"\n").unwrap();
+    assert!(catalog.reload_if_changed().unwrap());
+    assert_eq!(s.translate(&catalog), "servas, ciao");
+
+    // an empty file, e.g. caught while it is rewritten, keeps the current catalog
+    fs::write(&base, "").unwrap();
+    assert!(catalog.reload_if_changed().is_err());
+    assert_eq!(s.translate(&catalog), "servas, ciao");
+    fs::write(&base, "msgid \"hello\"\nmsgstr \"servus\"\n").unwrap();
+    assert!(catalog.reload_if_changed().unwrap());
+
+    // files modified long before they were read are only compared by modification time and size
+    let modified = std::time::SystemTime::now() - Duration::from_secs(60);
+    fs::File::options()
+        .write(true)
+        .open(&base)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    assert!(catalog.reload_if_changed().unwrap());
+    fs::write(&base, "msgid \"hello\"\nmsgstr \"servas\"\n").unwrap();
+    fs::File::options()
+        .write(true)
+        .open(&base)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    assert!(!catalog.reload_if_changed().unwrap());
+    assert_eq!(s.translate(&catalog), "servus, ciao");
+    fs::write(&base, "msgid \"hello\"\nmsgstr \"servus\"\n").unwrap();
+    assert!(catalog.reload_if_changed().unwrap());
+
+    let watcher = catalog.watch(Duration::from_millis(10)).unwrap();
+    fs::write(&extra, "msgid \"bye\"\nmsgstr \"pfiat di\"\nmsgid \"x\"\n").unwrap();
+    let mut output = s.translate(&catalog);
+    for _ in 0..500 {
+        if output != "servus, ciao" {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+        output = s.translate(&catalog);
+    }
+    assert_eq!(output, "servus, pfiat di");
+
+    fs::write(&extra, "msgid \"bye\"\nmsgstr \"broken").unwrap();
+    assert!(catalog.reload_if_changed().is_err());
+    assert_eq!(s.translate(&catalog), "servus, pfiat di");
+    drop(watcher);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_catalog_write_po() {
+    let mut catalog = Catalog::new();
+    catalog.set_header("Language: fr\nPlural-Forms: nplurals=2; plural=(n > 1);\n");
+    catalog.insert(Message::new("Say \"{0}\"", "Dites « {0} »"));
+    catalog.insert(Message::new("Path: C:\\{0}\ttab", ""));
+    catalog.insert(Message::new("line 1\nline 2\n", "ligne 1\nligne 2\n"));
+    catalog.insert(Message {
+        context: Some("verb".into()),
+        id: "Open".into(),
+        translations: ["Ouvrir".into()].into(),
+        fuzzy: true,
+        ..Message::default()
+    });
+    catalog.insert(Message {
+        id: "{0} file".into(),
+        id_plural: Some("{0} files".into()),
+        ..Message::default()
+    });
+
+    let po = catalog.to_po();
+    assert!(po.starts_with("msgid \"\"\nmsgstr \"\"\n\"Language: fr\\n\"\n"), "{po}");
+    assert!(
+        po.contains("\n#, fuzzy\nmsgctxt \"verb\"\nmsgid \"Open\"\nmsgstr \"Ouvrir\"\n"),
+        "{po}"
+    );
+    assert!(
+        po.contains("msgid_plural \"{0} files\"\nmsgstr[0] \"\"\nmsgstr[1] \"\"\n"),
+        "{po}"
+    );
+    assert!(po.contains("msgid \"\"\n\"line 1\\n\"\n\"line 2\\n\"\n"), "{po}");
+
+    let mut parsed = Catalog::parse_po(&po).unwrap();
+    assert_eq!(parsed.get(None, "{0} file").unwrap().translations, ["", ""]);
+    parsed.insert(Message {
+        id: "{0} file".into(),
+        id_plural: Some("{0} files".into()),
+        ..Message::default()
+    });
+    assert_eq!(parsed, catalog);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_recording_resolver() {
+    use crate::record::{Record, RecordingResolver};
+
+    let mut catalog = Catalog::new();
+    catalog.insert(Message::new("{0} of {1}", "{0} von {1}"));
+    catalog.insert(Message::new("page", "Seite"));
+    let resolver = RecordingResolver::new(catalog);
+
+    let s = I18nString::from_str("t!('{0} of {1}', t!('page'), t!('chapter {0}', t!('one')))").unwrap();
+    assert_eq!(s.translate(&resolver), "Seite von chapter one");
+    assert_eq!(s.translate(&resolver), "Seite von chapter one");
+
+    let records = resolver.records();
+    assert_eq!(records["{0} of {1}"], Record { requests: 2, misses: 0 });
+    assert_eq!(records["chapter {0}"], Record { requests: 2, misses: 2 });
+    assert_eq!(resolver.missing(), ["chapter {0}", "one"]);
+    assert_eq!(
+        resolver.missing_catalog().to_po(),
+        "msgid \"chapter {0}\"\nmsgstr \"\"\n\nmsgid \"one\"\nmsgstr \"\"\n"
+    );
+
+    resolver.clear();
+    assert!(resolver.records().is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_template_cache() {
+    use crate::{cache::TemplateCache, record::RecordingResolver};
+
+    let cache = TemplateCache::new(NoResolver, 2);
+    let options = TranslateOptions::new();
+    let templates = [
+        "{0} and {1}",
+        "{{0}} {1:>5} }}{0}{",
+        "{2} {x} }a {ab{ {0",
+        "",
+        "trailing }",
+        "dé{0:*^7}ü",
+    ];
+    for template in templates {
+        let s = I18nString::template(template, [I18nString::literal("a"), I18nString::literal("b")]);
+        assert_eq!(cache.translate(&s, &options), s.translate(NoResolver), "{template}");
+        assert_eq!(cache.translate(&s, &options), s.translate(NoResolver), "{template}");
+    }
+    assert_eq!(cache.len(), 2);
+
+    let mut catalog = Catalog::new();
+    catalog.insert(Message::new("{0} items", "{0} Artikel"));
+    let cache = TemplateCache::new(RecordingResolver::new(catalog), 8);
+    let resolver = cache.inner();
+    let de = TranslateOptions::new().locale(Locale::from_str("de").unwrap());
+    let s = I18nString::template("{0} items", [I18nString::value(Decimal::new(12345, 1))]);
+    for _ in 0..3 {
+        assert_eq!(cache.translate(&s, &de), "1.234,5 Artikel");
+        assert_eq!(cache.translate(&s, &options), "1,234.5 Artikel");
+    }
+    assert_eq!(resolver.records()["{0} items"].requests, 2);
+    assert_eq!(cache.len(), 2);
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.translate(&s, &options), "1,234.5 Artikel");
+    assert_eq!(resolver.records()["{0} items"].requests, 3);
+}
+
+#[test]
+fn test_limits() {
+    let deep = alloc::format!("{}'x'{}", "t!('{0}', ".repeat(100_000), ")".repeat(100_000));
+    assert!(I18nString::from_str(&deep).is_ok());
+    assert_eq!(
+        I18nString::parse_with_limits(&deep, &Limits::new().max_depth(128)),
+        Err(ParseError::LimitExceeded(LimitExceeded::Depth))
+    );
+
+    let wide = "t!('{0}{1}{2}', 'a', 'b', 'c')";
+    assert!(I18nString::parse_with_limits(wide, &Limits::new().max_nodes(4)).is_ok());
+    assert_eq!(
+        I18nString::parse_with_limits(wide, &Limits::new().max_nodes(3)),
+        Err(ParseError::LimitExceeded(LimitExceeded::Nodes))
+    );
+    assert_eq!(
+        I18nString::parse_with_limits("t!('{0}', ", &Limits::new()),
+        Err(ParseError::InvalidFormat)
+    );
+
+    // without limits, deep strings translate and round-trip completely
+    let nested = (0..200).fold(I18nString::literal("x"), |s, _| I18nString::template("<{0}>", [s]));
+    assert_eq!(nested.translate(NoResolver).len(), 401);
+    assert_eq!(I18nString::from_str(&nested.to_string()).unwrap(), nested);
+
+    let nested = (0..4).fold(I18nString::literal("x"), |s, _| I18nString::template("<{0}>", [s]));
+    let options = TranslateOptions::new().limits(Limits::new().max_depth(4));
+    assert_eq!(nested.try_translate_with(NoResolver, &options), Err(LimitExceeded::Depth));
+    let options = TranslateOptions::new().limits(Limits::new().max_depth(5));
+    assert_eq!(nested.try_translate_with(NoResolver, &options).unwrap(), "<<<<x>>>>");
+
+    struct Exploding;
+
+    impl Resolver for Exploding {
+        fn resolve<'s>(&'s self, _template: &'s str) -> Cow<'s, str> {
+            Cow::Borrowed("{0}{0}{0}{0}{0}{0}{0}{0}{0}{0}")
+        }
+    }
+
+    let bomb = (0..8).fold(I18nString::literal("lol"), |s, _| I18nString::template("{0}", [s]));
+    let options = TranslateOptions::new().limits(Limits::new().max_nodes(1000));
+    assert_eq!(bomb.try_translate_with(Exploding, &options), Err(LimitExceeded::Nodes));
+    let options = TranslateOptions::new().limits(Limits::new().max_output_bytes(1000));
+    assert_eq!(bomb.try_translate_with(Exploding, &options), Err(LimitExceeded::OutputBytes));
+    assert_eq!(bomb.translate_with(Exploding, &options).len(), 1000);
+
+    let options = TranslateOptions::new().limits(Limits::new().max_output_bytes(100));
+    let padded = I18nString::template("{0:>60000}", [I18nString::literal("x")]);
+    assert_eq!(
+        padded.try_translate_with(NoResolver, &options),
+        Err(LimitExceeded::OutputBytes)
+    );
+    let precise = I18nString::template("{0:.60000}", [I18nString::Value(Value::Integer(1))]);
+    assert_eq!(
+        precise.try_translate_with(NoResolver, &options),
+        Err(LimitExceeded::OutputBytes)
+    );
+
+    // widths and precisions beyond `std::fmt` make the placeholder invalid
+    let huge = I18nString::template("{0:.4000000000}|{0:>70000}", [I18nString::Value(Value::Integer(1))]);
+    assert_eq!(huge.translate(NoResolver), "{0:.4000000000}|{0:>70000}");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_deep_nesting_without_recursion() {
+    const DEPTH: usize = 200_000;
+
+    let handle = std::thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn(|| {
+            let input = alloc::format!("{}'x'{}", "t!('<{0}>',".repeat(DEPTH), ")".repeat(DEPTH));
+            let s = I18nString::from_str(&input).unwrap();
+            assert_eq!(s.to_string(), input);
+
+            let output = s.translate(NoResolver);
+            assert_eq!(output.len(), 2 * DEPTH + 1);
+            assert!(output.starts_with("<<<") && output.ends_with(">>>"));
+            drop(s);
+
+            let s = (0..DEPTH).fold(I18nString::literal("x"), |s, _| I18nString::template("{0}", [s]));
+            assert_eq!(s.translate(NoResolver), "x");
+            assert_eq!(s.translate_with(NoResolver, &TranslateOptions::new()), "x");
+        })
+        .unwrap();
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_visitors() {
+    use alloc::{boxed::Box, vec::Vec};
+
+    use compact_str::CompactString;
+
+    use crate::visit::{Fold, Visit, VisitMut, walk_template_fold, walk_template_mut};
+
+    #[derive(Default)]
+    struct Count {
+        literals: usize,
+        templates: usize,
+        values: usize,
+    }
+
+    impl Visit for Count {
+        fn visit_literal(&mut self, _literal: &str) {
+            self.literals += 1;
+        }
+
+        fn visit_template(&mut self, template: &TemplateKey, args: &[I18nString]) {
+            self.templates += 1;
+            crate::visit::walk_template(self, template, args);
+        }
+
+        fn visit_value(&mut self, _value: &Value) {
+            self.values += 1;
+        }
+    }
+
+    struct Prefix;
+
+    impl VisitMut for Prefix {
+        fn visit_template_mut(&mut self, template: &mut TemplateKey, args: &mut [I18nString]) {
+            template.text_mut().insert_str(0, "app.");
+            walk_template_mut(self, template, args);
+        }
+
+        fn visit_string_mut(&mut self, s: &mut I18nString) {
+            if let I18nString::Value(_) = s {
+                *s = I18nString::literal("?");
+            } else {
+                crate::visit::walk_string_mut(self, s);
+            }
+        }
+    }
+
+    struct Flatten(Vec<CompactString>, usize);
+
+    impl Fold for Flatten {
+        fn fold_template(&mut self, template: TemplateKey, args: Box<[I18nString]>) -> I18nString {
+            self.0.push(template.text().into());
+            self.1 += 1;
+            let s = walk_template_fold(self, template, args);
+            self.1 -= 1;
+            if self.1 > 0 { I18nString::literal(s.to_string()) } else { s }
+        }
+    }
+
+    let mut s = I18nString::from_str("t!('{0}: {1}', t!('total'), t!('{0} / {1}', int!(3), 'x'))").unwrap();
+    let mut count = Count::default();
+    count.visit_string(&s);
+    assert_eq!((count.literals, count.templates, count.values), (1, 3, 1));
+
+    Prefix.visit_string_mut(&mut s);
+    assert_eq!(
+        s.to_string(),
+        "t!('app.{0}: {1}',t!('app.total'),t!('app.{0} / {1}','?','x'))"
+    );
+
+    let mut flatten = Flatten(Vec::new(), 0);
+    let folded = flatten.fold_string(s);
+    assert_eq!(flatten.0, ["app.{0}: {1}", "app.total", "app.{0} / {1}"]);
+    assert_eq!(
+        folded.to_string(),
+        r#"t!('app.{0}: {1}',"t!('app.total')","t!('app.{0} / {1}','?','x')")"#
+    );
+}
+
+#[test]
+fn test_redaction() {
+    use crate::redact::Redaction;
+
+    let s = I18nString::template(
+        "{0} cannot read {1} ({2})",
+        [
+            I18nString::sensitive(I18nString::literal("bob@example.com")),
+            I18nString::sensitive(I18nString::template("file {0}", [I18nString::literal("/home/bob/a.txt")])),
+            I18nString::literal("denied"),
+        ],
+    );
+    let serialized = "t!('{0} cannot read {1} ({2})',s!('bob@example.com'),s!(t!('file {0}','/home/bob/a.txt')),'denied')";
+    assert_eq!(s.to_string(), serialized);
+    assert_eq!(I18nString::from_str(serialized).unwrap(), s);
+    assert_eq!(
+        I18nString::from_str("s!( 'x' )").unwrap(),
+        I18nString::sensitive(I18nString::literal("x"))
+    );
+    assert!(I18nString::from_str("s!('x', 'y')").is_err());
+    assert!(I18nString::from_str("s!()").is_err());
+
+    assert_eq!(
+        s.translate(NoResolver),
+        "bob@example.com cannot read file /home/bob/a.txt (denied)"
+    );
+    let options = TranslateOptions::new().redact(Redaction::Mask);
+    assert_eq!(
+        s.translate_with(NoResolver, &options),
+        "[redacted] cannot read [redacted] (denied)"
+    );
+    assert_eq!(
+        s.redacted(Redaction::Mask).to_string(),
+        "t!('{0} cannot read {1} ({2})',s!('[redacted]'),s!('[redacted]'),'denied')"
+    );
+
+    let options = TranslateOptions::new().redact(Redaction::Hash);
+    let hash_of = |arg: &str| {
+        let arg = I18nString::from_str(arg).unwrap();
+        I18nString::template("{0}", [I18nString::sensitive(arg)]).translate_with(NoResolver, &options)
+    };
+    let email = hash_of("'bob@example.com'");
+    let path = hash_of("t!('file {0}','/home/bob/a.txt')");
+    assert!(email.starts_with("[redacted:") && email.len() == "[redacted:]".len() + 16);
+    assert_ne!(email, path);
+    assert_eq!(
+        s.translate_with(NoResolver, &options),
+        alloc::format!("{email} cannot read {path} (denied)")
+    );
+    assert_eq!(
+        s.redacted(Redaction::Hash).to_string(),
+        alloc::format!("t!('{{0}} cannot read {{1}} ({{2}})',s!('{email}'),s!('{path}'),'denied')")
+    );
+}
+
+#[test]
+fn test_template_context() {
+    let s = I18nString::from_str("t!('{0} {1}', t!( ctx = 'verb' , 'Open'), t!(ctx='adjective','Open'))").unwrap();
+    assert_eq!(
+        s,
+        I18nString::template(
+            "{0} {1}",
+            [
+                I18nString::template(TemplateKey::new("Open").with_context("verb"), []),
+                I18nString::template(TemplateKey::new("Open").with_context("adjective"), []),
+            ]
+        )
+    );
+    assert_eq!(
+        s.to_string(),
+        "t!('{0} {1}',t!(ctx='verb','Open'),t!(ctx='adjective','Open'))"
+    );
+    assert_eq!(I18nString::from_str(&s.to_string()).unwrap(), s);
+    assert!(I18nString::from_str("t!(ctx='a', ctx='b', 'Open')").is_err());
+    assert!(I18nString::from_str("t!(context='a', 'Open')").is_err());
+    assert!(I18nString::from_str("t!(ctx='a')").is_err());
+
+    let catalog = Catalog::parse_po(
+        r#"
+msgctxt "verb"
+msgid "Open"
+msgstr "Öffnen"
+
+msgctxt "adjective"
+msgid "Open"
+msgstr "Offen"
+"#,
+    )
+    .unwrap();
+    assert_eq!(s.translate(&catalog), "Öffnen Offen");
+    assert_eq!(I18nString::template("Open", []).translate(&catalog), "Open");
+
+    #[cfg(feature = "std")]
+    {
+        use crate::{cache::TemplateCache, record::RecordingResolver, registry::ResolverRegistry};
+
+        let cache = TemplateCache::new(&catalog, 8);
+        assert_eq!(cache.translate(&s, &TranslateOptions::new()), "Öffnen Offen");
+        assert_eq!(cache.len(), 3);
+
+        let mut registry = ResolverRegistry::new();
+        registry.insert(Locale::from_str("de").unwrap(), catalog);
+        let resolver = RecordingResolver::new(registry.resolver(&[Locale::from_str("de-CH").unwrap()]).unwrap());
+        let s = I18nString::from_str("t!('{0}, {1}', t!(ctx='verb','Open'), t!(ctx='verb','Close'))").unwrap();
+        assert_eq!(s.translate(&resolver), "Öffnen, Close");
+        assert_eq!(resolver.missing(), ["verb\x04Close", "{0}, {1}"]);
+        assert_eq!(
+            resolver.missing_catalog().get(Some("verb"), "Close"),
+            Some(&Message {
+                context: Some("verb".into()),
+                ..Message::new("Close", "")
+            })
+        );
+    }
+}
+
+#[test]
+fn test_template_id() {
+    let s = I18nString::from_str("t!( ctx = 'file', id = 'err.nf' , 'Not found: {0}', t!(id='what','the file'))").unwrap();
+    let expected = I18nString::template(
+        TemplateKey::new("Not found: {0}").with_id("err.nf").with_context("file"),
+        [I18nString::template(TemplateKey::new("the file").with_id("what"), [])],
+    );
+    assert_eq!(s, expected);
+    assert_eq!(
+        s.to_string(),
+        "t!(id='err.nf',ctx='file','Not found: {0}',t!(id='what','the file'))"
+    );
+    assert_eq!(I18nString::from_str(&s.to_string()).unwrap(), s);
+    assert!(I18nString::from_str("t!(id='a', id='b', 'x')").is_err());
+    let I18nString::Template(key, _) = &s else { unreachable!() };
+    assert_eq!((key.lookup_key(), key.text()), ("err.nf", "Not found: {0}"));
+
+    assert_eq!(s.translate(NoResolver), "Not found: the file");
+
+    let mut catalog = Catalog::new();
+    catalog.insert(Message {
+        context: Some("file".into()),
+        ..Message::new("err.nf", "Introuvable : {0}")
+    });
+    catalog.insert(Message::new("Not found: {0}", "by text"));
+    catalog.insert(Message::new("the file", "le fichier"));
+    assert_eq!(s.translate(&catalog), "Introuvable : the file");
+
+    let edited = I18nString::template(
+        TemplateKey::new("Could not find {0}").with_id("err.nf").with_context("file"),
+        [],
+    );
+    assert_eq!(edited.translate(&catalog), "Introuvable : {0}");
+}
+
+#[test]
+fn test_template_notes() {
+    let key = TemplateKey::new("{0} of {1}")
+        .with_note("Progress of an upload\nkeep it short")
+        .with_placeholder_note(1, "total count")
+        .with_placeholder_note(0, "uploaded count");
+    assert_eq!(
+        key.placeholder_notes().collect::<alloc::vec::Vec<_>>(),
+        [(0, "uploaded count"), (1, "total count")]
+    );
+
+    let s = I18nString::template(
+        key,
+        [
+            I18nString::value(3),
+            I18nString::template(
+                TemplateKey::new("Open")
+                    .with_id("open")
+                    .with_context("verb")
+                    .with_note("A button"),
+                [],
+            ),
+        ],
+    );
+    let serialized = "t!(note='Progress of an upload\\nkeep it short',note0='uploaded count',note1='total count','{0} of {1}',int!(3),t!(id='open',ctx='verb',note='A button','Open'))";
+    assert_eq!(s.to_string(), serialized);
+    assert_eq!(I18nString::from_str(serialized).unwrap().to_string(), serialized);
+    assert_eq!(
+        I18nString::from_str("t!(note1 = 'b', note = 'a', 'x')").unwrap().to_string(),
+        "t!(note='a',note1='b','x')"
+    );
+    // notes do not identify a template
+    assert_eq!(
+        I18nString::template(TemplateKey::new("x").with_note("a"), []),
+        I18nString::template("x", [])
+    );
+    assert_ne!(TemplateKey::new("x").with_id("a"), TemplateKey::new("x"));
+    assert!(I18nString::from_str("t!(note1='a', note1='b', 'x')").is_err());
+    assert!(I18nString::from_str("t!(notes='a', 'x')").is_err());
+    assert_eq!(s.translate(NoResolver), "3 of Open");
+
+    let mut catalog = Catalog::new();
+    catalog.insert(Message::new("{0} of {1}", "{0} von {1}"));
+    catalog.extract(&s);
+    catalog.extract(&I18nString::template(
+        TemplateKey::new("{0} of {1}").with_note("Also used for pages"),
+        [],
+    ));
+    assert_eq!(
+        catalog.get(None, "{0} of {1}").unwrap().comments,
+        [
+            "Progress of an upload",
+            "keep it short",
+            "{0}: uploaded count",
+            "{1}: total count",
+            "Also used for pages"
+        ]
+    );
+    assert_eq!(catalog.get(None, "{0} of {1}").unwrap().translation(), Some("{0} von {1}"));
+    let open = catalog.get(Some("verb"), "open").unwrap();
+    assert_eq!(open.comments, ["Source text: Open", "A button"]);
+    assert_eq!(open.source_text().as_deref(), Some("Open"));
+    assert_eq!(catalog.get(None, "{0} of {1}").unwrap().source_text(), None);
+
+    let po = catalog.to_po();
+    assert!(
+        po.contains("#. {1}: total count\n#. Also used for pages\nmsgid \"{0} of {1}\"\nmsgstr \"{0} von {1}\"\n"),
+        "{po}"
+    );
+    assert_eq!(Catalog::parse_po(&po).unwrap(), catalog);
+}
+
+#[test]
+fn test_catalog_lint() {
+    use crate::lint::{IssueKind, Severity, lint, write_json};
+
+    let source = Catalog::parse_po(
+        r#"msgid "{0} of {1}"
+msgstr ""
+
+msgid "Save "
+msgstr ""
+
+msgid "OK"
+msgstr ""
+
+msgctxt "menu"
+msgid "Open"
+msgstr ""
+
+msgid "{0} file"
+msgid_plural "{0} files"
+msgstr[0] ""
+msgstr[1] ""
+"#,
+    )
+    .unwrap();
+    let by_id = Catalog::parse_po(
+        r#"#. Source text: {0} not found
+msgid "err.nf"
+msgstr ""
+
+#. Source text: Permission denied
+msgid "err.perm"
+msgstr ""
+
+msgid "Close"
+msgstr "Close window"
+"#,
+    )
+    .unwrap();
+    let translated = Catalog::parse_po(
+        r#"msgid ""
+msgstr "Plural-Forms: nplurals=3; plural=(n==1 ? 0 : n<5 ? 1 : 2);\n"
+
+msgid "{0} of {1}"
+msgstr "{0} z {2"
+
+msgid "Save "
+msgstr "Speichern"
+
+msgid "OK"
+msgstr "OK"
+
+#, fuzzy
+msgctxt "menu"
+msgid "Open"
+msgstr "Otwórz"
+
+msgid "{0} file"
+msgid_plural "{0} files"
+msgstr[0] "jeden plik"
+msgstr[1] "{0} pliki {3}"
+"#,
+    )
+    .unwrap();
+
+    // messages keyed by id are compared against their source text instead of the id
+    let de = Catalog::parse_po(
+        "msgid \"err.nf\"\nmsgstr \"{0} nicht gefunden\"\n\nmsgid \"err.perm\"\nmsgstr \"Permission denied\"\n\nmsgid \"Close\"\nmsgstr \"Close\"\n",
+    )
+    .unwrap();
+    let kinds: alloc::vec::Vec<_> = lint(&by_id, &de).into_iter().map(|i| (i.id, i.kind)).collect();
+    // plain messages compare against their msgid, not a differing source-language translation
+    assert_eq!(
+        kinds,
+        [
+            ("Close".into(), IssueKind::IdenticalToSource),
+            ("err.perm".into(), IssueKind::IdenticalToSource)
+        ]
+    );
+    let de = Catalog::parse_po("msgid \"err.nf\"\nmsgstr \"nicht gefunden\"\n").unwrap();
+    assert_eq!(
+        lint(&by_id, &de).into_iter().find(|i| i.id == "err.nf").unwrap().kind,
+        IssueKind::PlaceholderMismatch {
+            missing: alloc::vec![0],
+            extra: alloc::vec![]
+        }
+    );
+
+    let issues = lint(&source, &translated);
+    let kinds: alloc::vec::Vec<_> = issues.iter().map(|i| (i.id.as_str(), i.form, i.kind.clone())).collect();
+    assert_eq!(
+        kinds,
+        [
+            ("OK", None, IssueKind::IdenticalToSource),
+            ("Save ", None, IssueKind::TrailingWhitespace),
+            ("Open", None, IssueKind::Untranslated),
+            (
+                "{0} file",
+                None,
+                IssueKind::MissingPluralForms {
+                    expected: 3,
+                    missing: alloc::vec![2]
+                }
+            ),
+            (
+                "{0} file",
+                Some(1),
+                IssueKind::PlaceholderMismatch {
+                    missing: alloc::vec![],
+                    extra: alloc::vec![3]
+                }
+            ),
+            ("{0} of {1}", None, IssueKind::UnbalancedBraces),
+            (
+                "{0} of {1}",
+                None,
+                IssueKind::PlaceholderMismatch {
+                    missing: alloc::vec![1],
+                    extra: alloc::vec![]
+                }
+            ),
+        ]
+    );
+    assert_eq!(issues[2].context.as_deref(), Some("menu"));
+    assert_eq!(issues[2].kind.severity(), Severity::Warning);
+    assert_eq!(
+        issues[3].to_string(),
+        "error: msgid \"{0} file\": missing plural forms of 3: 2"
+    );
+
+    let mut json = alloc::string::String::new();
+    write_json(&mut json, &issues[2..4]).unwrap();
+    assert_eq!(
+        json,
+        r#"[{"severity":"warning","code":"untranslated","context":"menu","id":"Open","form":null,"message":"untranslated"},{"severity":"error","code":"missing-plural-forms","context":null,"id":"{0} file","form":null,"message":"missing plural forms of 3: 2","expected":3,"missing":[2]}]"#
+    );
+}
+
+#[test]
+fn test_catalog_formats() {
+    use crate::catalog::CatalogFormat;
+
+    let po = r#"msgid ""
+msgstr ""
+"Language: pl\n"
+"Plural-Forms: nplurals=3; plural=(n==1 ? 0 : n%10>=2 && n%10<=4 ? 1 : 2);\n"
+
+#. Shown after saving
+#. {0}: file name
+msgid "Saved {0} at {1:>8}"
+msgstr "Zapisano {0} o {1:>8} (100%) {{ok}}"
+
+#, fuzzy
+msgctxt "menu"
+msgid "Open"
+msgstr "Otwórz \"plik\" it's <b>\n"
+
+msgid "{0} file"
+msgid_plural "{0} files"
+msgstr[0] "{0} plik"
+msgstr[1] "{0} pliki"
+msgstr[2] "{0} plików"
+"#;
+    let catalog = Catalog::parse_po(po).unwrap();
+
+    for format in [
+        CatalogFormat::Po,
+        CatalogFormat::Json,
+        CatalogFormat::Toml,
+        CatalogFormat::Xliff,
+    ] {
+        let mut output = alloc::string::String::new();
+        catalog.write_as(&mut output, format).unwrap();
+        assert_eq!(Catalog::parse_as(&output, format).unwrap(), catalog, "{output}");
+    }
+
+    let mut xml = alloc::string::String::new();
+    catalog.write_as(&mut xml, CatalogFormat::AndroidXml).unwrap();
+    assert!(xml.contains(">Zapisano %1$s o %2$8s (100%%) {ok}</string>"), "{xml}");
+    assert!(xml.contains("<item quantity=\"few\">%1$s pliki</item>"), "{xml}");
+    let android = Catalog::parse_as(&xml, CatalogFormat::AndroidXml).unwrap();
+    let open = android.get(Some("menu"), "Open").unwrap();
+    assert_eq!(open.translations, ["Otwórz \"plik\" it's <b>\n"]);
+    assert_eq!(
+        android.get(None, "{0} file").unwrap().translations,
+        ["{0} plik", "{0} pliki", "{0} plików"]
+    );
+
+    let mut strings = alloc::string::String::new();
+    catalog.write_as(&mut strings, CatalogFormat::AppleStrings).unwrap();
+    assert!(strings.contains("\"{0} file[2]\" = \"%1$@ plików\";"), "{strings}");
+    let apple = Catalog::parse_as(&strings, CatalogFormat::AppleStrings).unwrap();
+    assert_eq!(
+        apple.get(None, "Saved {0} at {1:>8}").unwrap().comments,
+        ["Shown after saving", "{0}: file name"]
+    );
+    assert_eq!(apple.get(Some("menu"), "Open").unwrap().translations, open.translations);
+
+    let native = Catalog::parse_as(
+        "// sequential and typed directives\n\"a\" = \"%@ has %d%% of %s, %5.2f\";\n\"b\" = \"%2$@ %1$-4@ %q\";",
+        CatalogFormat::AppleStrings,
+    )
+    .unwrap();
+    assert_eq!(
+        native.get(None, "a").unwrap().translation(),
+        Some("{0} has {1}% of {2}, {3:>5.2}")
+    );
+    assert_eq!(native.get(None, "b").unwrap().translation(), Some("{1} {0:<4} %q"));
+    assert!(Catalog::parse_as("{\"messages\": [{\"translations\": []}]}", CatalogFormat::Json).is_err());
+    assert_eq!(
+        Catalog::parse_as("\"k[5]\" = \"x\";", CatalogFormat::AppleStrings)
+            .unwrap()
+            .get(None, "k")
+            .unwrap()
+            .translations[5],
+        "x"
+    );
+    for key in ["k[6]", "k[18446744073709551615]"] {
+        let input = alloc::format!("\"{key}\" = \"x\";");
+        assert!(
+            Catalog::parse_as(&input, CatalogFormat::AppleStrings).is_err(),
+            "input: {input}"
+        );
+    }
+}
+
+#[test]
+fn test_pretty_format() {
+    use crate::{pretty::Layout, redact::Redaction};
+
+    let s = I18nString::template(
+        TemplateKey::new("{0} uploaded {1}")
+            .with_id("upload.done")
+            .with_note("Shown after an upload")
+            .with_placeholder_note(0, "user name"),
+        [
+            I18nString::sensitive(I18nString::template(
+                "{0} <{1}>",
+                [I18nString::literal("Bob"), I18nString::literal("bob@example.com")],
+            )),
+            I18nString::template("{0} files", [I18nString::value(3)]),
+        ],
+    );
+
+    let pretty = alloc::format!("{s:#}");
+    assert_eq!(
+        pretty,
+        "t!(
+    id='upload.done',
+    note='Shown after an upload',
+    note0='user name',
+    '{0} uploaded {1}',
+    s!(t!('{0} <{1}>','Bob','bob@example.com')),
+    t!('{0} files',int!(3))
+)"
+    );
+    assert_eq!(I18nString::from_str(&pretty).unwrap(), s);
+
+    let narrow = s.pretty(Layout::new().width(0).indent(2)).to_string();
+    assert!(
+        narrow.contains("\n  s!(\n    t!(\n      '{0} <{1}>',\n      'Bob',"),
+        "{narrow}"
+    );
+    assert_eq!(I18nString::from_str(&narrow).unwrap(), s);
+
+    let redacted = s.pretty(Layout::new().width(0)).redact(Redaction::Mask).to_string();
+    assert!(redacted.contains("\n    s!('[redacted]'),\n"), "{redacted}");
+    assert_eq!(
+        alloc::format!("{:#}", s.redacted(Redaction::Mask)),
+        s.pretty(Layout::new()).redact(Redaction::Mask).to_string()
+    );
+
+    let short = I18nString::template("Hi", []);
+    assert_eq!(alloc::format!("{short:#}"), short.to_string());
+}
+
+#[test]
+fn test_escape_sequences() {
+    use core::fmt::Write;
+
+    use crate::escape::{Escaped, escape, escape_bytes, unescape};
+
+    let text: alloc::string::String = "a'b\\c\r\n\t\0 é"
+        .chars()
+        .chain(('\0'..='\u{a0}').filter(|c| c.is_control()))
+        .collect();
+    let escaped = escape(&text);
+    assert!(!escaped.contains(char::is_control), "{escaped}");
+    assert!(escaped.starts_with("a\\'b\\\\c\\r\\n\\t\\0 é\\0\\u{1}\\u{2}"), "{escaped}");
+    assert!(
+        escaped.contains("\\u{1f}\\u{7f}\\u{80}") && escaped.ends_with("\\u{9f}"),
+        "{escaped}"
+    );
+    assert_eq!(escape_bytes(text.as_bytes()).as_ref(), escaped.as_bytes());
+    assert_eq!(unescape(&escaped).unwrap(), text);
+
+    let mut written = alloc::string::String::new();
+    for c in text.chars() {
+        Escaped::new(&mut written).write_char(c).unwrap();
+    }
+    assert_eq!(written, escaped);
+
+    let s = I18nString::template("{0}\r\n", [I18nString::literal(text.as_str())]);
+    let serialized = s.to_string();
+    assert!(serialized.starts_with("t!('{0}\\r\\n',\"a'b"), "{serialized}");
+    assert_eq!(I18nString::from_str(&serialized).unwrap(), s);
+    assert_eq!(I18nString::parse_strict(&serialized, &Limits::default()).unwrap(), s);
+
+    assert_eq!(
+        I18nString::from_str("'\\r\\0\\u{1F600}'").unwrap(),
+        I18nString::literal("\r\0\u{1F600}")
+    );
+    assert_eq!(I18nString::from_str("'\\u{zz}'").unwrap(), I18nString::literal("u{zz}"));
+    for invalid in ["'\\q'", "'\\u{zz}'", "'\\u{110000}'", "'\\u{}'", "'\\u{1234567}'"] {
+        assert!(I18nString::parse_strict(invalid, &Limits::default()).is_err(), "{invalid}");
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_streaming_escape() {
+    use std::io::{self, Read, Write};
+
+    use crate::escape::{Escaped, Unescaped, escape};
+
+    /// Writer accepting one byte per call.
+    struct Trickle(Vec<u8>);
+
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.extend(buf.first());
+            Ok(buf.len().min(1))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Reader returning one byte per call.
+    struct Drip<'a>(&'a [u8]);
+
+    impl Read for Drip<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some((first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buf[0] = *first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    let text = "it's C:\\dir\r\n\u{85}\u{1b}[0m é ".repeat(500);
+    let expected = escape(&text);
+
+    let mut escaped = Escaped::new(Trickle(Vec::new()));
+    assert_eq!(escaped.write(b"a'\xc2").unwrap(), 3);
+    assert_eq!(escaped.write(b"\x85\xc2").unwrap(), 2);
+    assert_eq!(escaped.write(b"\xa9\xc2").unwrap(), 2);
+    escaped.flush().unwrap();
+    assert_eq!(escaped.into_inner().0, b"a\\'\\u{85}\xc2\xa9\xc2");
+
+    for chunk in [1, 7, 4096] {
+        let mut escaped = Escaped::new(Trickle(Vec::new()));
+        let mut rest = text.as_bytes();
+        while !rest.is_empty() {
+            let written = escaped.write(&rest[..chunk.min(rest.len())]).unwrap();
+            rest = &rest[written..];
+        }
+        assert_eq!(escaped.into_inner().0, expected.as_bytes());
+    }
+
+    let mut unescaped = alloc::string::String::new();
+    Unescaped::new(Drip(expected.as_bytes()))
+        .read_to_string(&mut unescaped)
+        .unwrap();
+    assert_eq!(unescaped, text);
+    let mut unescaped = alloc::string::String::new();
+    Unescaped::new(expected.as_bytes()).read_to_string(&mut unescaped).unwrap();
+    assert_eq!(unescaped, text);
+
+    for invalid in [&b"abc\\"[..], b"\\q", b"\\u{41", b"\\u{110000}", b"\\u{1234567}"] {
+        let error = Unescaped::new(Drip(invalid)).read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{invalid:?}");
+    }
+}
+
+#[test]
+fn test_quoting_styles() {
+    let s = I18nString::template(
+        "{0} a modifié {1}",
+        [I18nString::literal("l'utilisateur"), I18nString::literal(r"C:\Users\'#x")],
+    );
+    let serialized = s.to_string();
+    assert_eq!(
+        serialized,
+        r###"t!('{0} a modifié {1}',"l'utilisateur",r##'C:\Users\'#x'##)"###
+    );
+    assert_eq!(I18nString::from_str(&serialized).unwrap(), s);
+    assert_eq!(I18nString::parse_strict(&serialized, &Limits::default()).unwrap(), s);
+
+    // single-quoted data written by earlier versions still parses
+    let legacy = r"t!('{0} a modifié {1}','l\'utilisateur','C:\\Users\\\'#x')";
+    assert_eq!(I18nString::from_str(legacy).unwrap(), s);
+
+    let mixed = r#"t!(ctx="menu",id=r'file',"Fichier {0}",r#'"x"'#)"#;
+    let parsed = I18nString::from_str(mixed).unwrap();
+    assert_eq!(I18nString::from_str(&parsed.to_string()).unwrap(), parsed);
+    assert!(parsed.to_string().contains(r#"'"x"'"#), "{parsed}");
+
+    for invalid in [r"t!(r'abc)", r"t!(r#'abc')", "t!(\"abc')", r"t!(r#abc)"] {
+        assert!(I18nString::from_str(invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_message_stream() {
+    use alloc::vec::Vec;
+
+    use crate::stream::Messages;
+
+    let log = concat!(
+        "t!('a {0}','x')'b' int!(5)\n",
+        "'unterminated\n",
+        "t!(\n",
+        "    'c {0}',\n",
+        "    s!('d')\n",
+        ")\n",
+        "t!('e', oops) 'lost'\n",
+        "t!('{0}',t!('{0}',t!('{0}','f')))\n",
+        "  'last'",
+    );
+    let expected = [
+        (0..15, Ok(I18nString::template("a {0}", [I18nString::literal("x")]))),
+        (15..18, Ok(I18nString::literal("b"))),
+        (19..26, Ok(I18nString::Value(Value::Integer(5)))),
+        (27..40, Err(ParseError::InvalidFormat)),
+        (
+            41..71,
+            Ok(I18nString::template(
+                "c {0}",
+                [I18nString::sensitive(I18nString::literal("d"))],
+            )),
+        ),
+        (72..92, Err(ParseError::InvalidFormat)),
+        (93..126, Err(ParseError::LimitExceeded(LimitExceeded::Depth))),
+        (129..135, Ok(I18nString::literal("last"))),
+    ];
+
+    let limits = Limits::new().max_depth(3);
+    let messages: Vec<_> = Messages::new(log).limits(limits).collect();
+    assert_eq!(messages, expected);
+    for (span, result) in &messages {
+        if let Ok(s) = result {
+            assert_eq!(&I18nString::from_str(&log[span.clone()]).unwrap(), s);
+        }
+    }
+
+    // an unfinished string at the end resumes after its first line
+    let messages: Vec<_> = Messages::new("t!('a',\n'b'\n").collect();
+    assert_eq!(
+        messages,
+        [(0..7, Err(ParseError::InvalidFormat)), (8..11, Ok(I18nString::literal("b")))]
+    );
+
+    // an invalid string spanning lines resumes after its first line
+    let messages: Vec<_> = Messages::new("t!('Saved {0}', 'a'\nt!('Bye')\n'ok'\n").collect();
+    assert_eq!(
+        messages,
+        [
+            (0..19, Err(ParseError::InvalidFormat)),
+            (20..29, Ok(I18nString::template("Bye", []))),
+            (30..34, Ok(I18nString::literal("ok"))),
+        ]
+    );
+
+    #[cfg(feature = "std")]
+    {
+        use std::io::{BufReader, Cursor};
+
+        use crate::stream::MessageReader;
+
+        let read: Vec<_> = MessageReader::new(BufReader::with_capacity(1, log.as_bytes()))
+            .limits(limits)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(read, expected);
+
+        let mut reader = MessageReader::new(Cursor::new(&b"t!('a',\n'\xff'\n'b'\n"[..]));
+        assert_eq!(reader.next().unwrap().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(reader.next().unwrap().unwrap(), (12..15, Ok(I18nString::literal("b"))));
+        assert!(reader.next().is_none());
+
+        let unterminated = alloc::format!("t!({}'a')\n'b'\n", "\n".repeat(10_000));
+        let read: Vec<_> = MessageReader::new(Cursor::new(unterminated))
+            .max_lines(3)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            read,
+            [
+                (0..3, Err(ParseError::LimitExceeded(LimitExceeded::Lines))),
+                (10_003..10_006, Ok(I18nString::literal("a"))),
+                (10_006..10_007, Err(ParseError::InvalidFormat)),
+                (10_008..10_011, Ok(I18nString::literal("b"))),
+            ]
+        );
+    }
+}
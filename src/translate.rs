@@ -1,89 +1,284 @@
-use alloc::string::String;
-use core::str::FromStr;
+use alloc::{borrow::Cow, string::String, sync::Arc, vec::Vec};
 
-use crate::{I18nString, Resolver};
+use crate::{
+    I18nString, Resolver, TemplateKey, TranslateOptions, bidi,
+    limit::LimitExceeded,
+    locale_data,
+    locale_data::LocaleData,
+    redact,
+    template::{CompiledTemplate, FormatSpec, Segment, TemplateStore, next_segment},
+    value,
+};
 
-fn translate_to<R: Resolver + ?Sized>(input: &I18nString, output: &mut String, resolver: &R) {
-    match input {
-        I18nString::Literal(s) => {
-            output.push_str(s);
+fn locale_data(options: &TranslateOptions) -> LocaleData {
+    options.locale.as_ref().map_or(locale_data::ROOT, locale_data::lookup)
+}
+
+/// A resolved template being rendered.
+enum Source<'a> {
+    Resolved(Cow<'a, str>, usize),
+    Compiled(Arc<CompiledTemplate>, usize),
+}
+
+impl Source<'_> {
+    fn text(&self) -> &str {
+        match self {
+            Source::Resolved(template, _) => template,
+            Source::Compiled(compiled, _) => &compiled.source,
         }
-        I18nString::Template(template, args) => {
-            enum ParseState {
-                Normal,
-                HitLeftBrace { pos: usize },
-                HitRightBrace,
+    }
+
+    fn next_segment(&mut self) -> Option<Segment> {
+        match self {
+            Source::Resolved(template, pos) => next_segment(template, pos),
+            Source::Compiled(compiled, idx) => {
+                let segment = compiled.segments.get(*idx)?.clone();
+                *idx += 1;
+                Some(segment)
             }
+        }
+    }
+}
 
-            let template = resolver.resolve(template);
+/// A template on the translation stack.
+struct Frame<'a> {
+    source: Source<'a>,
+    args: &'a [I18nString],
+    depth: usize,
+    /// Start offset and spec if the template is an argument of its parent.
+    arg: Option<(usize, Option<FormatSpec>)>,
+}
 
-            let mut state = ParseState::Normal;
+/// State of a single translation.
+struct Translator<'a, R: ?Sized> {
+    resolver: &'a R,
+    options: &'a TranslateOptions,
+    store: Option<&'a dyn TemplateStore>,
+    nodes: usize,
+}
 
-            for (idx, c) in template.char_indices() {
-                match state {
-                    ParseState::Normal => {
-                        if c == '{' {
-                            state = ParseState::HitLeftBrace { pos: idx };
-                        } else if c == '}' {
-                            state = ParseState::HitRightBrace;
-                        } else {
-                            output.push(c);
-                        }
+impl<'a, R: Resolver + ?Sized> Translator<'a, R> {
+    fn check_output(&self, output: &str) -> Result<(), LimitExceeded> {
+        if output.len() > self.options.limits.max_output_bytes {
+            Err(LimitExceeded::OutputBytes)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Count a node at the given depth.
+    fn enter(&mut self, depth: usize) -> Result<(), LimitExceeded> {
+        self.nodes += 1;
+        if self.nodes > self.options.limits.max_nodes {
+            Err(LimitExceeded::Nodes)
+        } else if depth > self.options.limits.max_depth {
+            Err(LimitExceeded::Depth)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn source(&self, template: &'a TemplateKey) -> Source<'a> {
+        let resolver = self.resolver;
+        let resolve = || match template.id() {
+            Some(id) => resolver
+                .lookup_in_context(template.context(), id)
+                .unwrap_or(Cow::Borrowed(template.text())),
+            None => resolver.resolve_in_context(template.context(), template.text()),
+        };
+        match self.store {
+            Some(store) => Source::Compiled(
+                store.get_or_compile(self.options.locale.as_ref(), template, &mut || {
+                    CompiledTemplate::new(&resolve())
+                }),
+                0,
+            ),
+            None => Source::Resolved(resolve(), 0),
+        }
+    }
+
+    /// Write a literal, a value or a redacted argument, or push a frame for a template.
+    fn begin(
+        &mut self,
+        input: &'a I18nString,
+        output: &mut String,
+        depth: usize,
+        arg: Option<(usize, Option<FormatSpec>)>,
+        stack: &mut Vec<Frame<'a>>,
+    ) -> Result<(), LimitExceeded> {
+        self.enter(depth)?;
+
+        let options = self.options;
+        let mut input = input;
+        while let I18nString::Sensitive(inner) = input {
+            if let Some(redaction) = options.redaction {
+                let _ = redact::write_redacted(output, inner, redaction);
+                break;
+            }
+            self.enter(depth)?;
+            input = inner;
+        }
+
+        match input {
+            I18nString::Sensitive(_) => {}
+            I18nString::Literal(s) => match options.literal_escape {
+                Some(context) => output.push_str(&context.escape(s)),
+                None => output.push_str(s),
+            },
+            I18nString::Value(value) => match arg {
+                Some((start, Some(spec))) => {
+                    // every fraction digit takes at least a byte, reject huge precisions before padding with zeros
+                    if let Some(precision) = spec.precision
+                        && start.saturating_add(precision) > options.limits.max_output_bytes
+                    {
+                        return Err(LimitExceeded::OutputBytes);
                     }
-                    ParseState::HitLeftBrace { pos } => {
-                        if c == '}' {
-                            match usize::from_str(&template[pos + 1..idx]).ok().and_then(|idx| args.get(idx)) {
-                                Some(arg) => {
-                                    translate_to(arg, output, resolver);
-                                }
-                                None => {
-                                    // ignore invalid format or no arg
-                                    output.push('{');
-                                    output.push_str(&template[pos + 1..idx]);
-                                    output.push('}');
-                                }
-                            }
+                    value::format_to(output, value, &locale_data(options), spec.precision);
+                    spec.apply(output, start, true);
+                    return self.finish_arg(output, start, None);
+                }
+                _ => value::format_to(output, value, &locale_data(options), None),
+            },
+            I18nString::Template(template, args) => {
+                stack.push(Frame {
+                    source: self.source(template),
+                    args,
+                    depth,
+                    arg,
+                });
+                return Ok(());
+            }
+        }
 
-                            state = ParseState::Normal;
-                        } else if c == '{' {
-                            output.push(c);
-                            state = ParseState::Normal;
+        match arg {
+            Some((start, spec)) => self.finish_arg(output, start, spec.as_ref()),
+            None => self.check_output(output),
+        }
+    }
+
+    /// Apply the spec and bidi isolation to an argument written since `start`.
+    fn finish_arg(&self, output: &mut String, start: usize, spec: Option<&FormatSpec>) -> Result<(), LimitExceeded> {
+        if let Some(spec) = spec {
+            spec.apply(output, start, false);
+        }
+
+        if let Some(target) = self.options.isolation {
+            let isolate = match bidi::direction(&output[start..]) {
+                Some(direction) if direction == target => return self.check_output(output),
+                Some(direction) => direction.isolate(),
+                None => bidi::FSI,
+            };
+            output.insert(start, isolate);
+            output.push(bidi::PDI);
+        }
+        self.check_output(output)
+    }
+
+    /// Translate with an explicit stack of templates instead of recursion.
+    fn translate_to(&mut self, input: &'a I18nString, output: &mut String) -> Result<(), LimitExceeded> {
+        let mut stack: Vec<Frame<'a>> = Vec::new();
+        self.begin(input, output, 1, None, &mut stack)?;
+
+        while let Some(frame) = stack.last_mut() {
+            match frame.source.next_segment() {
+                Some(Segment::Text(range) | Segment::Invalid(range)) => output.push_str(&frame.source.text()[range]),
+                Some(Segment::Argument { index, spec, raw }) => match frame.args.get(index) {
+                    Some(arg) => {
+                        let start = output.len();
+                        if let Some(width) = spec.and_then(|spec| spec.width) {
+                            // every padding char takes at least a byte, reject huge widths before padding
+                            if start.saturating_add(width) > self.options.limits.max_output_bytes {
+                                return Err(LimitExceeded::OutputBytes);
+                            }
                         }
+                        let depth = frame.depth + 1;
+                        self.begin(arg, output, depth, Some((start, spec)), &mut stack)?;
                     }
-                    ParseState::HitRightBrace => {
-                        if c == '}' {
-                            output.push(c);
-                            state = ParseState::Normal;
-                        } else {
-                            // ignore invalid format
-                            output.push('}');
-                            output.push(c);
-                            state = ParseState::Normal;
-                        }
+                    // ignore no arg
+                    None => output.push_str(&frame.source.text()[raw]),
+                },
+                None => {
+                    if let Some(Frame {
+                        arg: Some((start, spec)),
+                        ..
+                    }) = stack.pop()
+                    {
+                        self.finish_arg(output, start, spec.as_ref())?;
                     }
                 }
             }
+            self.check_output(output)?;
+        }
+        Ok(())
+    }
+}
 
-            match state {
-                ParseState::Normal => {}
-                ParseState::HitLeftBrace { pos } => {
-                    // ignore unclosed left brace
-                    output.push('{');
-                    output.push_str(&template[pos + 1..]);
-                }
-                ParseState::HitRightBrace => {
-                    // ignore unclosed right brace
-                    output.push('}');
-                }
-            }
+/// Translate `input` into `output`.
+///
+/// When a limit is exceeded the output is cut to at most `max_output_bytes`.
+pub(crate) fn translate_to<R: Resolver + ?Sized>(
+    input: &I18nString,
+    output: &mut String,
+    resolver: &R,
+    options: &TranslateOptions,
+    store: Option<&dyn TemplateStore>,
+) -> Result<(), LimitExceeded> {
+    let mut translator = Translator {
+        resolver,
+        options,
+        store,
+        nodes: 0,
+    };
+    let result = translator.translate_to(input, output);
+    if result.is_err() {
+        let mut end = options.limits.max_output_bytes.min(output.len());
+        while !output.is_char_boundary(end) {
+            end -= 1;
         }
+        output.truncate(end);
     }
+    result
 }
 
 impl I18nString {
     pub fn translate<R: Resolver>(&self, resolver: R) -> String {
+        self.translate_with(resolver, &TranslateOptions::default())
+    }
+
+    /// Translate the `I18nString` with the given options.
+    ///
+    /// # Examples
+    ///
+    /// Isolate arguments in a right-to-left template.
+    /// ```
+    /// use i18n_string::{I18nString, NoResolver, TranslateOptions, bidi::Direction};
+    ///
+    /// let s = I18nString::template("הקובץ {0} נמחק", [I18nString::literal("report.pdf")]);
+    /// let options = TranslateOptions::new().isolate(Direction::Rtl);
+    /// assert_eq!(s.translate_with(NoResolver, &options), "הקובץ \u{2066}report.pdf\u{2069} נמחק");
+    /// ```
+    pub fn translate_with<R: Resolver>(&self, resolver: R, options: &TranslateOptions) -> String {
         let mut res = String::with_capacity(32);
-        translate_to(self, &mut res, &resolver);
+        let _ = translate_to(self, &mut res, &resolver, options, None);
         res
     }
+
+    /// Translate the `I18nString` with the given options, failing if a limit of the options is exceeded.
+    ///
+    /// # Examples
+    ///
+    /// Basic example.
+    /// ```
+    /// use i18n_string::{I18nString, NoResolver, TranslateOptions, limit::{LimitExceeded, Limits}};
+    ///
+    /// let s = I18nString::template("{0}{0}{0}{0}", [I18nString::literal("spam")]);
+    /// let options = TranslateOptions::new().limits(Limits::new().max_output_bytes(10));
+    /// assert_eq!(s.try_translate_with(NoResolver, &options), Err(LimitExceeded::OutputBytes));
+    /// assert_eq!(s.translate_with(NoResolver, &options), "spamspamsp");
+    /// ```
+    pub fn try_translate_with<R: Resolver>(&self, resolver: R, options: &TranslateOptions) -> Result<String, LimitExceeded> {
+        let mut res = String::with_capacity(32);
+        translate_to(self, &mut res, &resolver, options, None)?;
+        Ok(res)
+    }
 }
@@ -0,0 +1,262 @@
+use alloc::string::String;
+use core::{
+    fmt::{Display, Formatter, Write},
+    str::FromStr,
+};
+
+use compact_str::CompactString;
+
+use crate::{InvalidFormat, locale_data, locale_data::LocaleData, number::Fixed};
+
+const MAX_SCALE: u32 = 18;
+
+/// A fixed-point decimal number, `mantissa * 10^-scale`.
+///
+/// The scale is kept as is, so `1.50` and `1.5` are different values and format with a different number of fraction digits.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::value::Decimal;
+///
+/// let d: Decimal = "-12.50".parse().unwrap();
+/// assert_eq!(d, Decimal::new(-1250, 2));
+/// assert_eq!(d.to_string(), "-12.50");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Decimal {
+    mantissa: i64,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Create a new `Decimal` from a mantissa and a scale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is greater than 18.
+    pub const fn new(mantissa: i64, scale: u32) -> Self {
+        assert!(scale <= MAX_SCALE, "scale must not exceed 18");
+        Self { mantissa, scale }
+    }
+
+    /// Get the mantissa.
+    pub const fn mantissa(&self) -> i64 {
+        self.mantissa
+    }
+
+    /// Get the scale, the number of digits after the decimal point.
+    pub const fn scale(&self) -> u32 {
+        self.scale
+    }
+}
+
+macro_rules! impl_decimal_from_int {
+    ($($typ:ty),*) => {
+        $(
+            impl From<$typ> for Decimal {
+                fn from(value: $typ) -> Self {
+                    Self::new(value.into(), 0)
+                }
+            }
+        )*
+    };
+}
+
+impl_decimal_from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl FromStr for Decimal {
+    type Err = InvalidFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        let (int, frac) = match digits.split_once('.') {
+            Some((int, frac)) => (int, frac),
+            None => (digits, ""),
+        };
+        if int.is_empty() || !int.bytes().all(|c| c.is_ascii_digit()) || !frac.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(InvalidFormat);
+        }
+        if digits.ends_with('.') || frac.len() > MAX_SCALE as usize {
+            return Err(InvalidFormat);
+        }
+
+        let mut mantissa: i64 = 0;
+        for c in int.bytes().chain(frac.bytes()) {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add((c - b'0') as i64))
+                .ok_or(InvalidFormat)?;
+        }
+
+        Ok(Self::new(if negative { -mantissa } else { mantissa }, frac.len() as u32))
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let number = Fixed::new(self.mantissa as i128, self.scale, None);
+        if number.negative {
+            f.write_char('-')?;
+        }
+        f.write_str(&number.int)?;
+        if !number.frac.is_empty() {
+            f.write_char('.')?;
+            f.write_str(&number.frac)?;
+        }
+        Ok(())
+    }
+}
+
+/// A typed argument, formatted according to the locale at translation time.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```
+/// use i18n_string::{I18nString, NoResolver, TranslateOptions, value::Value};
+///
+/// let s = I18nString::template("Total: {0}", [I18nString::value(Value::Currency("1234.5".parse().unwrap(), "EUR".into()))]);
+/// assert_eq!(s.to_string(), "t!('Total: {0}',cur!(1234.5,'EUR'))");
+/// let options = TranslateOptions::new().locale("de-DE".parse().unwrap());
+/// assert_eq!(s.translate_with(NoResolver, &options), "Total: 1.234,50\u{a0}€");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum Value {
+    /// An integer.
+    Integer(i64),
+    /// A decimal number.
+    Decimal(Decimal),
+    /// A percentage, stored as a ratio (`0.25` is `25%`).
+    Percent(Decimal),
+    /// A monetary amount with an ISO 4217 currency code.
+    Currency(Decimal, CompactString),
+    /// A date, as seconds since the Unix epoch in UTC.
+    Date(i64),
+    /// A date and time, as seconds since the Unix epoch in UTC.
+    DateTime(i64),
+    /// A duration in seconds.
+    Duration(u64),
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<Decimal> for Value {
+    fn from(value: Decimal) -> Self {
+        Value::Decimal(value)
+    }
+}
+
+fn push_pattern(output: &mut String, data: &LocaleData, pattern: &str, symbol: &str, number: &Fixed) {
+    if number.negative {
+        output.push_str(data.number.minus);
+    }
+    for c in pattern.chars() {
+        match c {
+            '#' => data.number.push_unsigned(output, number),
+            '¤' => output.push_str(symbol),
+            _ => output.push(c),
+        }
+    }
+}
+
+/// Convert days since the Unix epoch into a `(year, month, day)` civil date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn push_date_time(output: &mut String, data: &LocaleData, pattern: &str, timestamp: i64) {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(86400));
+    let seconds = timestamp.rem_euclid(86400);
+    let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut count = 1;
+        while c.is_ascii_alphabetic() && chars.next_if_eq(&c).is_some() {
+            count += 1;
+        }
+
+        let field = match c {
+            'y' => year,
+            'M' => month as i64,
+            'd' => day as i64,
+            'H' => hour,
+            'h' => (hour + 11) % 12 + 1,
+            'm' => minute,
+            's' => second,
+            'a' => {
+                output.push_str(data.am_pm[(hour >= 12) as usize]);
+                continue;
+            }
+            _ => {
+                output.push(c);
+                continue;
+            }
+        };
+        let mut buf = String::new();
+        let _ = write!(buf, "{field:0count$}");
+        data.number.numbering_system.push_digits(output, &buf);
+    }
+}
+
+pub(crate) fn format_to(output: &mut String, value: &Value, data: &LocaleData, precision: Option<usize>) {
+    match value {
+        Value::Integer(v) => push_pattern(output, data, "#", "", &Fixed::new(*v as i128, 0, precision)),
+        Value::Decimal(d) => push_pattern(output, data, "#", "", &Fixed::new(d.mantissa as i128, d.scale, precision)),
+        Value::Percent(d) => {
+            let number = match d.scale {
+                0 | 1 => Fixed::new(d.mantissa as i128 * 10i128.pow(2 - d.scale), 0, precision),
+                scale => Fixed::new(d.mantissa as i128, scale - 2, precision),
+            };
+            push_pattern(output, data, data.percent, "", &number);
+        }
+        Value::Currency(d, code) => {
+            let (symbol, digits) = locale_data::currency(code);
+            let number = Fixed::new(d.mantissa as i128, d.scale, Some(precision.unwrap_or(digits)));
+            push_pattern(output, data, data.currency, symbol, &number);
+        }
+        Value::Date(timestamp) => push_date_time(output, data, data.date, *timestamp),
+        Value::DateTime(timestamp) => {
+            let mut pattern = data.date_time;
+            while let Some(pos) = pattern.find('{') {
+                output.push_str(&pattern[..pos]);
+                pattern = &pattern[pos..];
+                if let Some(rest) = pattern.strip_prefix("{0}") {
+                    push_date_time(output, data, data.time, *timestamp);
+                    pattern = rest;
+                } else if let Some(rest) = pattern.strip_prefix("{1}") {
+                    push_date_time(output, data, data.date, *timestamp);
+                    pattern = rest;
+                } else {
+                    output.push('{');
+                    pattern = &pattern[1..];
+                }
+            }
+            output.push_str(pattern);
+        }
+        Value::Duration(seconds) => {
+            let mut buf = String::new();
+            let _ = write!(buf, "{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60);
+            data.number.numbering_system.push_digits(output, &buf);
+        }
+    }
+}
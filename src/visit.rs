@@ -0,0 +1,211 @@
+use alloc::boxed::Box;
+
+use compact_str::CompactString;
+
+use crate::{I18nString, TemplateKey, value::Value};
+
+/// Walk an `I18nString` tree by reference.
+///
+/// Every method defaults to walking the children of its node, override the methods of interest and
+/// call the matching `walk_*` function to keep walking.
+///
+/// # Examples
+///
+/// Collect all template keys.
+/// ```
+/// use i18n_string::{I18nString, TemplateKey, visit::{Visit, walk_template}};
+///
+/// struct Keys(Vec<String>);
+///
+/// impl Visit for Keys {
+///     fn visit_template(&mut self, template: &TemplateKey, args: &[I18nString]) {
+///         self.0.push(template.text().into());
+///         walk_template(self, template, args);
+///     }
+/// }
+///
+/// let s: I18nString = "t!('{0} of {1}', t!('page'), 'x')".parse().unwrap();
+/// let mut keys = Keys(Vec::new());
+/// keys.visit_string(&s);
+/// assert_eq!(keys.0, ["{0} of {1}", "page"]);
+/// ```
+pub trait Visit {
+    /// Visit any node.
+    fn visit_string(&mut self, s: &I18nString) {
+        walk_string(self, s);
+    }
+
+    /// Visit a literal string.
+    fn visit_literal(&mut self, literal: &str) {
+        let _ = literal;
+    }
+
+    /// Visit a template and its arguments.
+    fn visit_template(&mut self, template: &TemplateKey, args: &[I18nString]) {
+        walk_template(self, template, args);
+    }
+
+    /// Visit a typed value.
+    fn visit_value(&mut self, value: &Value) {
+        let _ = value;
+    }
+
+    /// Visit a sensitive argument.
+    fn visit_sensitive(&mut self, inner: &I18nString) {
+        self.visit_string(inner);
+    }
+}
+
+/// Dispatch a node to the method of its kind.
+pub fn walk_string<V: Visit + ?Sized>(visitor: &mut V, s: &I18nString) {
+    match s {
+        I18nString::Literal(literal) => visitor.visit_literal(literal),
+        I18nString::Template(template, args) => visitor.visit_template(template, args),
+        I18nString::Value(value) => visitor.visit_value(value),
+        I18nString::Sensitive(inner) => visitor.visit_sensitive(inner),
+    }
+}
+
+/// Visit the arguments of a template.
+pub fn walk_template<V: Visit + ?Sized>(visitor: &mut V, template: &TemplateKey, args: &[I18nString]) {
+    let _ = template;
+    for arg in args {
+        visitor.visit_string(arg);
+    }
+}
+
+/// Walk an `I18nString` tree by mutable reference, modifying it in place.
+///
+/// # Examples
+///
+/// Upper case all literals.
+/// ```
+/// use compact_str::CompactString;
+/// use i18n_string::{I18nString, visit::VisitMut};
+///
+/// struct Upper;
+///
+/// impl VisitMut for Upper {
+///     fn visit_literal_mut(&mut self, literal: &mut CompactString) {
+///         *literal = literal.to_uppercase().into();
+///     }
+/// }
+///
+/// let mut s: I18nString = "t!('hello {0}', 'world')".parse().unwrap();
+/// Upper.visit_string_mut(&mut s);
+/// assert_eq!(s.to_string(), "t!('hello {0}','WORLD')");
+/// ```
+pub trait VisitMut {
+    /// Visit any node, it may be replaced entirely.
+    fn visit_string_mut(&mut self, s: &mut I18nString) {
+        walk_string_mut(self, s);
+    }
+
+    /// Visit a literal string.
+    fn visit_literal_mut(&mut self, literal: &mut CompactString) {
+        let _ = literal;
+    }
+
+    /// Visit a template and its arguments.
+    fn visit_template_mut(&mut self, template: &mut TemplateKey, args: &mut [I18nString]) {
+        walk_template_mut(self, template, args);
+    }
+
+    /// Visit a typed value.
+    fn visit_value_mut(&mut self, value: &mut Value) {
+        let _ = value;
+    }
+
+    /// Visit a sensitive argument.
+    fn visit_sensitive_mut(&mut self, inner: &mut I18nString) {
+        self.visit_string_mut(inner);
+    }
+}
+
+/// Dispatch a node to the method of its kind.
+pub fn walk_string_mut<V: VisitMut + ?Sized>(visitor: &mut V, s: &mut I18nString) {
+    match s {
+        I18nString::Literal(literal) => visitor.visit_literal_mut(literal),
+        I18nString::Template(template, args) => visitor.visit_template_mut(template, args),
+        I18nString::Value(value) => visitor.visit_value_mut(value),
+        I18nString::Sensitive(inner) => visitor.visit_sensitive_mut(inner),
+    }
+}
+
+/// Visit the arguments of a template.
+pub fn walk_template_mut<V: VisitMut + ?Sized>(visitor: &mut V, template: &mut TemplateKey, args: &mut [I18nString]) {
+    let _ = template;
+    for arg in args {
+        visitor.visit_string_mut(arg);
+    }
+}
+
+/// Transform an `I18nString` tree by value, every node may be replaced by a node of any kind.
+///
+/// # Examples
+///
+/// Redact all arguments of templates.
+/// ```
+/// use i18n_string::{I18nString, value::Value, visit::Fold};
+///
+/// struct Redact;
+///
+/// impl Fold for Redact {
+///     fn fold_literal(&mut self, _literal: compact_str::CompactString) -> I18nString {
+///         I18nString::literal("***")
+///     }
+///
+///     fn fold_value(&mut self, _value: Value) -> I18nString {
+///         I18nString::literal("***")
+///     }
+/// }
+///
+/// let s: I18nString = "t!('{0} paid {1}', 'alice', cur!(12.5,'EUR'))".parse().unwrap();
+/// assert_eq!(Redact.fold_string(s).to_string(), "t!('{0} paid {1}','***','***')");
+/// ```
+pub trait Fold {
+    /// Fold any node.
+    fn fold_string(&mut self, s: I18nString) -> I18nString {
+        walk_string_fold(self, s)
+    }
+
+    /// Fold a literal string.
+    fn fold_literal(&mut self, literal: CompactString) -> I18nString {
+        I18nString::Literal(literal)
+    }
+
+    /// Fold a template and its arguments.
+    fn fold_template(&mut self, template: TemplateKey, args: Box<[I18nString]>) -> I18nString {
+        walk_template_fold(self, template, args)
+    }
+
+    /// Fold a typed value.
+    fn fold_value(&mut self, value: Value) -> I18nString {
+        I18nString::Value(value)
+    }
+
+    /// Fold a sensitive argument, the folded argument stays sensitive by default.
+    fn fold_sensitive(&mut self, inner: I18nString) -> I18nString {
+        I18nString::sensitive(self.fold_string(inner))
+    }
+}
+
+/// Dispatch a node to the method of its kind.
+pub fn walk_string_fold<F: Fold + ?Sized>(folder: &mut F, mut s: I18nString) -> I18nString {
+    // `I18nString` implements `Drop`, so the fields are taken out instead of moved
+    match &mut s {
+        I18nString::Literal(literal) => folder.fold_literal(core::mem::take(literal)),
+        I18nString::Template(template, args) => folder.fold_template(core::mem::take(template), core::mem::take(args)),
+        I18nString::Value(value) => folder.fold_value(core::mem::replace(value, Value::Integer(0))),
+        I18nString::Sensitive(inner) => folder.fold_sensitive(core::mem::replace(
+            &mut **inner,
+            I18nString::Literal(CompactString::default()),
+        )),
+    }
+}
+
+/// Fold the arguments of a template and rebuild it.
+pub fn walk_template_fold<F: Fold + ?Sized>(folder: &mut F, template: TemplateKey, args: Box<[I18nString]>) -> I18nString {
+    let args = args.into_vec().into_iter().map(|arg| folder.fold_string(arg)).collect();
+    I18nString::Template(template, args)
+}
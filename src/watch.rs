@@ -0,0 +1,270 @@
+use std::{
+    borrow::Cow,
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, RwLock, Weak,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use crate::{Resolver, catalog::Catalog};
+
+/// Time the files must stay unchanged after reading them before a reload is accepted.
+const SETTLE_DELAY: Duration = Duration::from_millis(50);
+
+/// Coarsest resolution of modification times on common file systems, e.g. FAT.
+const MTIME_RESOLUTION: Duration = Duration::from_secs(2);
+
+/// Modification stamp of an existing file: modification time, size and content hash.
+#[derive(Copy, Clone, PartialEq, Eq)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+    hash: u64,
+}
+
+fn stamp_of(path: &Path, content: &[u8]) -> io::Result<Option<Stamp>> {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Ok(Some(Stamp {
+        modified: fs::metadata(path)?.modified()?,
+        len: content.len() as u64,
+        hash: hasher.finish(),
+    }))
+}
+
+/// Read the stamp of a file, `None` if it does not exist.
+fn stamp(path: &Path) -> io::Result<Option<Stamp>> {
+    match fs::read(path) {
+        Ok(content) => stamp_of(path, &content),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Check whether a file differs from its stamp, reading it only if its metadata cannot tell.
+///
+/// The content is hashed only if the metadata matches and the file was modified within [`MTIME_RESOLUTION`]
+/// of `verified`, the time its content was last read, since a rewrite of the same size in that window may keep
+/// the modification time. Returns `None` if the file changed, otherwise whether its content was read.
+fn unchanged(path: &Path, loaded: Option<&Stamp>, verified: SystemTime) -> io::Result<Option<bool>> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(loaded.is_none().then_some(false)),
+        Err(e) => return Err(e),
+    };
+    let Some(loaded) = loaded else { return Ok(None) };
+    if (metadata.modified()?, metadata.len()) != (loaded.modified, loaded.len) {
+        return Ok(None);
+    }
+    if verified
+        .duration_since(loaded.modified)
+        .is_ok_and(|age| age > MTIME_RESOLUTION)
+    {
+        return Ok(Some(false));
+    }
+    Ok((stamp(path)?.as_ref() == Some(loaded)).then_some(true))
+}
+
+struct State {
+    catalog: Arc<Catalog>,
+    stamps: Vec<Option<Stamp>>,
+    /// Time before the contents of the files were last read.
+    verified: SystemTime,
+}
+
+/// A catalog backed by `.po` files, reloaded when the files change.
+///
+/// Changes are detected by polling the modification time and size of the files, so it works on any file system.
+/// A file is only read to compare its content if it was modified too recently for its modification time to tell.
+/// Messages of later files override earlier ones, and a failed reload keeps the current catalog.
+///
+/// # Examples
+///
+/// Basic example.
+/// ```no_run
+/// use std::{sync::Arc, time::Duration};
+///
+/// use i18n_string::{I18nString, watch::WatchedCatalog};
+///
+/// let catalog = Arc::new(WatchedCatalog::open("locales/de/messages.po").unwrap());
+/// let _watcher = catalog.watch(Duration::from_millis(500)).unwrap();
+///
+/// let s = I18nString::template("Hello", []);
+/// println!("{}", s.translate(&catalog));
+/// ```
+pub struct WatchedCatalog {
+    paths: Vec<PathBuf>,
+    state: RwLock<State>,
+}
+
+impl WatchedCatalog {
+    /// Load a catalog from a single `.po` file.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::open_all([path])
+    }
+
+    /// Load a catalog merged from multiple `.po` files.
+    pub fn open_all<P: AsRef<Path>, I: IntoIterator<Item = P>>(paths: I) -> io::Result<Self> {
+        let paths: Vec<PathBuf> = paths.into_iter().map(|p| p.as_ref().to_path_buf()).collect();
+        let state = Self::load(&paths, false)?;
+        Ok(Self {
+            paths,
+            state: RwLock::new(state),
+        })
+    }
+
+    /// Load and merge the files, `reloading` also rejects empty files and waits for the files to settle.
+    fn load(paths: &[PathBuf], reloading: bool) -> io::Result<State> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+        let verified = SystemTime::now();
+
+        let mut catalog = Catalog::new();
+        let mut stamps = Vec::with_capacity(paths.len());
+        for path in paths {
+            let content = fs::read(path)?;
+            stamps.push(stamp_of(path, &content)?);
+
+            let content = String::from_utf8(content).map_err(|_| invalid("catalog is not valid UTF-8"))?;
+            // a file caught between truncating and writing it is empty
+            if reloading && content.trim().is_empty() {
+                return Err(invalid("catalog is empty"));
+            }
+            let loaded = Catalog::parse_po(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            if catalog.header().is_empty() {
+                catalog.set_header(loaded.header());
+            }
+            catalog.extend(loaded.messages().cloned());
+        }
+
+        if reloading {
+            thread::sleep(SETTLE_DELAY);
+        }
+        for (path, loaded) in paths.iter().zip(&stamps) {
+            if stamp(path)? != *loaded {
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "catalog changed while loading"));
+            }
+        }
+        Ok(State {
+            catalog: Arc::new(catalog),
+            stamps,
+            verified,
+        })
+    }
+
+    /// Get the watched files.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Get a snapshot of the current catalog.
+    pub fn catalog(&self) -> Arc<Catalog> {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).catalog.clone()
+    }
+
+    /// Reload the catalog if any file changed since the last load, returns whether it was reloaded.
+    ///
+    /// The files must stay unchanged for a short delay after reading them. A file that is empty, invalid or
+    /// changed while loading is an error and the current catalog is kept.
+    pub fn reload_if_changed(&self) -> io::Result<bool> {
+        let now = SystemTime::now();
+        let (stamps, verified) = {
+            let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+            (state.stamps.clone(), state.verified)
+        };
+
+        let mut read = false;
+        for (path, loaded) in self.paths.iter().zip(&stamps) {
+            match unchanged(path, loaded.as_ref(), verified)? {
+                Some(content_read) => read |= content_read,
+                None => {
+                    let loaded = Self::load(&self.paths, true)?;
+                    *self.state.write().unwrap_or_else(|e| e.into_inner()) = loaded;
+                    return Ok(true);
+                }
+            }
+        }
+
+        if read {
+            // the contents matched at `now`, only rewrites within the resolution of `now` must be read again
+            let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+            if state.stamps == stamps {
+                state.verified = now;
+            }
+        }
+        Ok(false)
+    }
+
+    /// Poll the files for changes on a background thread until the returned `Watcher` is dropped.
+    ///
+    /// Reload errors, e.g. a file caught in the middle of being written, are ignored and retried on the next poll.
+    /// Fails if the thread cannot be spawned.
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> io::Result<Watcher> {
+        let catalog: Weak<Self> = Arc::downgrade(self);
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = thread::Builder::new().name("i18n-catalog-watcher".into()).spawn({
+            let stop = stop.clone();
+            move || {
+                while !stop.load(Ordering::Acquire) {
+                    thread::park_timeout(interval);
+                    match catalog.upgrade() {
+                        Some(catalog) => {
+                            let _ = catalog.reload_if_changed();
+                        }
+                        None => break,
+                    }
+                }
+            }
+        })?;
+
+        Ok(Watcher {
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Resolver for WatchedCatalog {
+    fn resolve<'s>(&'s self, template: &'s str) -> Cow<'s, str> {
+        self.lookup(template).unwrap_or(Cow::Borrowed(template))
+    }
+
+    fn lookup<'s>(&'s self, template: &'s str) -> Option<Cow<'s, str>> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state.catalog.lookup(template).map(|t| Cow::Owned(t.into_owned()))
+    }
+
+    fn resolve_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Cow<'s, str> {
+        self.lookup_in_context(context, template).unwrap_or(Cow::Borrowed(template))
+    }
+
+    fn lookup_in_context<'s>(&'s self, context: Option<&'s str>, template: &'s str) -> Option<Cow<'s, str>> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state
+            .catalog
+            .lookup_in_context(context, template)
+            .map(|t| Cow::Owned(t.into_owned()))
+    }
+}
+
+/// Handle of a background watcher thread, stops the thread when dropped.
+pub struct Watcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
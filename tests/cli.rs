@@ -0,0 +1,129 @@
+#![cfg(feature = "std")]
+
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+/// Run the binary with the given stdin, returns the exit code, stdout and stderr.
+fn run(args: &[&str], stdin: &str) -> (i32, String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_i18n-string"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+/// Create an empty temporary directory for a test.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("i18n-string-cli-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_parse() {
+    let input = "t!( 'a {0}',\"b\" )  'c'\nbad line\nt!('d')";
+    let (code, stdout, stderr) = run(&["parse"], input);
+    assert_eq!(code, 1);
+    assert_eq!(stdout, "t!('a {0}','b')\n'c'\nt!('d')\n");
+    assert_eq!(stderr, "-:2: invalid format\n");
+
+    // pretty output spans lines and parses back
+    let long = format!("t!('{{0}} {{1}}','{}',t!('{}'))", "a".repeat(60), "b".repeat(60));
+    let (code, pretty, _) = run(&["parse", "--pretty"], &long);
+    assert_eq!(code, 0);
+    assert!(pretty.lines().count() > 1, "{pretty}");
+    assert_eq!(run(&["parse"], &pretty), (0, format!("{long}\n"), String::new()));
+
+    let deep = format!("{}'x'{}", "t!('{0}',".repeat(100_000), ")".repeat(100_000));
+    let (code, stdout, _) = run(&["parse"], &deep);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.trim_end(), deep);
+}
+
+#[test]
+fn test_tree() {
+    let (code, stdout, _) = run(&["tree"], "t!('a {0}', t!('b', 'x'), s!(int!(3)))");
+    assert_eq!(code, 0);
+    assert_eq!(
+        stdout,
+        "t!('a {0}')\n├── {0} t!('b')\n│   └── {0} 'x'\n└── {1} s!\n    └── int!(3)\n"
+    );
+
+    let deep = format!("{}'x'{}", "t!('{0}',".repeat(2_000), ")".repeat(2_000));
+    let (code, stdout, _) = run(&["tree"], &deep);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.lines().count(), 2_001);
+}
+
+#[test]
+fn test_translate() {
+    let dir = temp_dir("translate");
+    let catalog = dir.join("de.po");
+    fs::write(&catalog, "msgid \"{0} items\"\nmsgstr \"{0} Artikel\"\n").unwrap();
+
+    let args = ["translate", "--catalog", catalog.to_str().unwrap(), "--locale", "de"];
+    let (code, stdout, _) = run(&args, "t!('{0} items', int!(1234))\nt!('other')\n");
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "1.234 Artikel\nother\n");
+
+    let (code, _, stderr) = run(&["translate", "--locale", "!!"], "");
+    assert_eq!(code, 2);
+    assert_eq!(stderr, "i18n-string: invalid locale !!\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_convert_and_lint() {
+    let dir = temp_dir("lint");
+    let source = dir.join("messages.pot");
+    let po = dir.join("de.po");
+    let json = dir.join("de.json");
+    fs::write(
+        &source,
+        "msgid \"{0} of {1}\"\nmsgstr \"\"\n\nmsgid \"Cancel\"\nmsgstr \"\"\n",
+    )
+    .unwrap();
+    fs::write(
+        &po,
+        "msgid \"{0} of {1}\"\nmsgstr \"{0} von {2}\"\n\nmsgid \"Cancel\"\nmsgstr \"Abbrechen\"\n",
+    )
+    .unwrap();
+
+    let (code, _, _) = run(&["convert", po.to_str().unwrap(), json.to_str().unwrap()], "");
+    assert_eq!(code, 0);
+
+    let (code, stdout, _) = run(&["lint", source.to_str().unwrap(), json.to_str().unwrap()], "");
+    assert_eq!(code, 1);
+    assert_eq!(
+        stdout,
+        format!(
+            "{}: error: msgid \"{{0}} of {{1}}\": placeholder mismatch, missing {{1}}, unexpected {{2}}\n",
+            json.display()
+        )
+    );
+
+    let (code, stdout, _) = run(&["lint", "--json", source.to_str().unwrap(), po.to_str().unwrap()], "");
+    assert_eq!(code, 1);
+    let key = format!(
+        "{{\"{}\":[{{\"severity\":\"error\",\"code\":\"placeholder-mismatch\"",
+        po.display()
+    );
+    assert!(stdout.starts_with(&key), "{stdout}");
+
+    fs::remove_dir_all(&dir).unwrap();
+}